@@ -1,5 +1,6 @@
 use crate::database::manager::DatabaseManager;
 use crate::core::ai_service::AIService;
+use crate::core::git_history::GitHistory;
 use tauri::State;
 
 #[tauri::command(rename_all = "snake_case")]
@@ -28,11 +29,25 @@ Keep it concise."#.to_string();
         .map_err(|e| e.to_string())
 }
 
+/// Explain `diff_content`, or a specific commit when `path` and `commit` are given
 #[tauri::command(rename_all = "snake_case")]
 pub async fn explain_diff(
-    diff_content: String,
+    diff_content: Option<String>,
+    path: Option<String>,
+    commit: Option<String>,
     state: State<'_, DatabaseManager>,
 ) -> Result<String, String> {
+    let diff_content = match (path, commit) {
+        (Some(path), Some(commit)) => {
+            let repo = git2::Repository::open(&path)
+                .map_err(|e| format!("Failed to open git repo: {}", e))?;
+            GitHistory::commit_detail(&repo, &commit, None)
+                .map_err(|e| e.to_string())?
+                .to_patch_text()
+        }
+        _ => diff_content.ok_or("Provide diff_content, or path and commit")?,
+    };
+
     let system_prompt = r#"You are an expert code reviewer.
 Explain the following code diff in simple terms.
 Focus on the intent and potential impact.
@@ -0,0 +1,77 @@
+use crate::commands::git_changes::{commit_signature, configured_identity};
+use crate::core::commit_port::{CommitPorter, PortReport};
+use crate::core::mapping_history::parse_mappings;
+use crate::core::provenance::Provenance;
+use crate::core::repo_routes::find_route;
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use git2::Repository;
+use sea_orm::EntityTrait;
+use tauri::State;
+
+/// Port commits of a route's source repository into its target as patches.
+/// Paths are rewritten through the route's mappings; each ported commit keeps its author and
+/// message and records where it came from in trailers. Unless `allow_partial` is set, porting
+/// stops at the first commit with hunks that do not apply and nothing of it is committed.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn port_commits(
+    route_id: String,
+    commits: Vec<String>,
+    allow_partial: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<PortReport, String> {
+    let db = &state.connection;
+    if commits.is_empty() {
+        return Err("No commits selected".to_string());
+    }
+
+    let route = find_route(db, &route_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Route not found")?;
+    if route.archived_at.is_some() {
+        return Err("Route is archived".to_string());
+    }
+    let source_id = route.main_repo_id.clone().ok_or("No source repo")?;
+    let target_id = route.slave_repo_id.clone().ok_or("No target repo")?;
+    let source = repositories::Entity::find_by_id(source_id.clone())
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Source repo not found")?;
+    let target = repositories::Entity::find_by_id(target_id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Target repo not found")?;
+    let mappings = parse_mappings(route.mappings.as_deref()).map_err(|e| e.to_string())?;
+    if mappings.is_empty() {
+        return Err("No mappings defined".to_string());
+    }
+    let identity = configured_identity(&state).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let source_repo = Repository::open(&source.local_path)
+            .map_err(|e| format!("Failed to open source repo: {}", e))?;
+        let target_repo = Repository::open(&target.local_path)
+            .map_err(|e| format!("Failed to open target repo: {}", e))?;
+        let committer = commit_signature(&target_repo, &identity)?;
+
+        CommitPorter {
+            source: &source_repo,
+            target: &target_repo,
+            mappings: &mappings,
+            provenance: Provenance {
+                route_id: route.id.clone(),
+                source_repo_id: Some(source_id),
+                source_commits: Vec::new(),
+            },
+            committer,
+            allow_partial: allow_partial.unwrap_or(false),
+        }
+        .port(&commits)
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
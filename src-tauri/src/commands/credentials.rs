@@ -0,0 +1,92 @@
+use crate::commands::repo::find_repository;
+use crate::core::credentials::{CredentialVault, VaultStatus};
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use sea_orm::{ActiveModelTrait, Set};
+use serde::Serialize;
+use tauri::State;
+
+/// Repository auth settings as shown to the frontend; the secret itself never leaves the backend
+#[derive(Serialize)]
+pub struct RepositoryCredentials {
+    pub auth_type: String,
+    pub username: Option<String>,
+    pub key_path: Option<String>,
+    pub has_secret: bool,
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_repository_credentials(
+    repo_id: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<RepositoryCredentials, String> {
+    let repo = find_repository(&state.connection, &repo_id).await?;
+    Ok(RepositoryCredentials {
+        auth_type: repo.auth_type,
+        username: repo.auth_username,
+        key_path: repo.auth_key_path,
+        has_secret: repo.auth_secret.is_some_and(|s| !s.is_empty()),
+    })
+}
+
+/// Store auth settings for a repository. `auth_type` is "none", "ssh" or "token".
+/// `secret` (token or SSH key passphrase) is encrypted before saving; leave it out to keep
+/// the current one, or pass an empty string to remove it.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_repository_credentials(
+    repo_id: String,
+    auth_type: String,
+    username: Option<String>,
+    secret: Option<String>,
+    key_path: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    if !matches!(auth_type.as_str(), "none" | "ssh" | "token") {
+        return Err(format!("Unknown auth type: {}", auth_type));
+    }
+    let repo = find_repository(&state.connection, &repo_id).await?;
+
+    let mut active: repositories::ActiveModel = repo.into();
+    active.auth_type = Set(auth_type);
+    active.auth_username = Set(username.filter(|u| !u.is_empty()));
+    active.auth_key_path = Set(key_path.filter(|p| !p.is_empty()));
+    match secret {
+        Some(s) if s.is_empty() => active.auth_secret = Set(None),
+        Some(s) => {
+            let encrypted = CredentialVault::encrypt(&s).map_err(|e| e.to_string())?;
+            active.auth_secret = Set(Some(encrypted));
+        }
+        None => {}
+    }
+    active
+        .update(&state.connection)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_credential_vault_status() -> VaultStatus {
+    CredentialVault::status()
+}
+
+#[tauri::command]
+pub async fn unlock_credentials(
+    passphrase: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    CredentialVault::unlock(&passphrase, &state.connection)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Protect stored secrets with a passphrase, or switch back to the key file with `None`
+#[tauri::command]
+pub async fn set_credential_passphrase(
+    passphrase: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    CredentialVault::set_passphrase(passphrase.as_deref(), &state.connection)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,7 @@
-use crate::database::entities::{routes, repositories};
+use crate::database::entities::repositories;
 use crate::database::manager::DatabaseManager;
 use crate::core::diff::{DiffEngine, DiffSummary};
+use crate::core::repo_routes::find_route;
 use crate::commands::route::MappingRule;
 use sea_orm::{EntityTrait};
 use tauri::State;
@@ -11,7 +12,7 @@ pub async fn preview_route_diff(route_id: String, state: State<'_, DatabaseManag
     let db = &state.connection;
 
     // 1. Get Route
-    let route = routes::Entity::find_by_id(&route_id).one(db).await.map_err(|e| e.to_string())?
+    let route = find_route(db, &route_id).await.map_err(|e| e.to_string())?
         .ok_or("Route not found")?;
 
     // 2. Get Source and Target Repos
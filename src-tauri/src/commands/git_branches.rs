@@ -0,0 +1,265 @@
+use crate::commands::git_ops::{open_repo, CommitInfo};
+use chrono::{TimeZone, Utc};
+use git2::{Branch, BranchType, Oid, Repository};
+use serde::Serialize;
+
+#[derive(Serialize, Debug)]
+pub struct BranchComparison {
+    pub base: String,
+    pub head: String,
+    pub merge_base: Option<String>,
+    /// Commits on `head` that are not on `base`
+    pub ahead: usize,
+    /// Commits on `base` that are not on `head`
+    pub behind: usize,
+    pub ahead_commits: Vec<CommitInfo>,
+    pub behind_commits: Vec<CommitInfo>,
+}
+
+/// Create a local branch at `start_point` (any revision, default HEAD), optionally switching to it
+#[tauri::command(rename_all = "snake_case")]
+pub fn create_git_branch(
+    path: String,
+    name: String,
+    start_point: Option<String>,
+    checkout: Option<bool>,
+) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let start = start_point
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let commit = repo
+        .revparse_single(&start)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|e| format!("Invalid start point '{}': {}", start, e))?;
+
+    repo.branch(&name, &commit, false)
+        .map_err(|e| format!("Failed to create branch: {}", e))?;
+
+    if checkout.unwrap_or(false) {
+        checkout_local_branch(&repo, &name)?;
+    }
+    Ok(())
+}
+
+/// Create a local branch tracking `remote_branch` (e.g. `origin/feature`).
+/// The local name defaults to the remote branch name without the remote prefix.
+#[tauri::command(rename_all = "snake_case")]
+pub fn create_tracking_branch(
+    path: String,
+    remote_branch: String,
+    name: Option<String>,
+    checkout: Option<bool>,
+) -> Result<String, String> {
+    let repo = open_repo(&path)?;
+    let local_name = create_tracking_branch_in(&repo, &remote_branch, name.as_deref())?;
+
+    if checkout.unwrap_or(false) {
+        checkout_local_branch(&repo, &local_name)?;
+    }
+    Ok(local_name)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn rename_git_branch(
+    path: String,
+    old_name: String,
+    new_name: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let mut branch = find_local_branch(&repo, &old_name)?;
+    branch
+        .rename(&new_name, force.unwrap_or(false))
+        .map_err(|e| format!("Failed to rename branch: {}", e))?;
+    Ok(())
+}
+
+/// Delete a local branch. Unless `force` is set, the branch must be merged into its upstream
+/// or into HEAD, like `git branch -d`.
+#[tauri::command(rename_all = "snake_case")]
+pub fn delete_git_branch(path: String, name: String, force: Option<bool>) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let mut branch = find_local_branch(&repo, &name)?;
+    if branch.is_head() {
+        return Err(format!("Cannot delete the checked out branch '{}'", name));
+    }
+
+    if !force.unwrap_or(false) {
+        let tip = branch_tip(&branch)?;
+        let merge_target = match branch.upstream() {
+            Ok(upstream) => Some(branch_tip(&upstream)?),
+            Err(_) => repo.head().ok().and_then(|h| h.target()),
+        };
+        let merged = match merge_target {
+            Some(target) => {
+                target == tip
+                    || repo
+                        .graph_descendant_of(target, tip)
+                        .map_err(|e| e.to_string())?
+            }
+            None => false,
+        };
+        if !merged {
+            return Err(format!(
+                "Branch '{}' is not fully merged; delete with force to discard its commits",
+                name
+            ));
+        }
+    }
+
+    branch
+        .delete()
+        .map_err(|e| format!("Failed to delete branch: {}", e))
+}
+
+/// Set the upstream of a local branch to a remote branch (e.g. `origin/main`), or unset it with `None`
+#[tauri::command(rename_all = "snake_case")]
+pub fn set_branch_upstream(
+    path: String,
+    branch: String,
+    upstream: Option<String>,
+) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let mut local = find_local_branch(&repo, &branch)?;
+    local
+        .set_upstream(upstream.as_deref().filter(|u| !u.is_empty()))
+        .map_err(|e| format!("Failed to set upstream: {}", e))
+}
+
+/// Ahead/behind counts and the differing commits of `head` relative to `base`.
+/// Both accept branch names or any revision; `limit` caps each commit list (default 100).
+#[tauri::command(rename_all = "snake_case")]
+pub fn compare_git_branches(
+    path: String,
+    base: String,
+    head: String,
+    limit: Option<usize>,
+) -> Result<BranchComparison, String> {
+    let repo = open_repo(&path)?;
+    let resolve = |rev: &str| {
+        repo.revparse_single(rev)
+            .and_then(|obj| obj.peel_to_commit())
+            .map(|c| c.id())
+            .map_err(|e| format!("Cannot resolve '{}': {}", rev, e))
+    };
+    let base_oid = resolve(&base)?;
+    let head_oid = resolve(&head)?;
+
+    let (ahead, behind) = repo
+        .graph_ahead_behind(head_oid, base_oid)
+        .map_err(|e| e.to_string())?;
+    let merge_base = repo.merge_base(base_oid, head_oid).ok();
+    let limit = limit.unwrap_or(100);
+
+    Ok(BranchComparison {
+        ahead_commits: commits_between(&repo, head_oid, base_oid, limit)?,
+        behind_commits: commits_between(&repo, base_oid, head_oid, limit)?,
+        base,
+        head,
+        merge_base: merge_base.map(|o| o.to_string()),
+        ahead,
+        behind,
+    })
+}
+
+/// Create a local branch tracking `remote_branch` and return its name
+pub(crate) fn create_tracking_branch_in(
+    repo: &Repository,
+    remote_branch: &str,
+    name: Option<&str>,
+) -> Result<String, String> {
+    let remote = repo
+        .find_branch(remote_branch, BranchType::Remote)
+        .map_err(|_| format!("Remote branch '{}' not found", remote_branch))?;
+    let local_name = match name.filter(|n| !n.is_empty()) {
+        Some(n) => n.to_string(),
+        None => remote_branch
+            .split_once('/')
+            .map(|(_, rest)| rest.to_string())
+            .ok_or_else(|| format!("'{}' is not a remote branch name", remote_branch))?,
+    };
+
+    let commit = remote.get().peel_to_commit().map_err(|e| e.to_string())?;
+    let mut local = repo
+        .branch(&local_name, &commit, false)
+        .map_err(|e| format!("Failed to create branch: {}", e))?;
+    local
+        .set_upstream(Some(remote_branch))
+        .map_err(|e| format!("Failed to set upstream: {}", e))?;
+    Ok(local_name)
+}
+
+/// Safely check out an existing local branch
+pub(crate) fn checkout_local_branch(repo: &Repository, name: &str) -> Result<(), String> {
+    let branch = find_local_branch(repo, name)?;
+    let commit = branch.get().peel_to_commit().map_err(|e| e.to_string())?;
+    repo.checkout_tree(
+        commit.as_object(),
+        Some(git2::build::CheckoutBuilder::new().safe()),
+    )
+    .map_err(|e| format!("Checkout failed (conflict?): {}", e))?;
+    repo.set_head(&format!("refs/heads/{}", name))
+        .map_err(|e| format!("Failed to set HEAD: {}", e))
+}
+
+fn find_local_branch<'r>(repo: &'r Repository, name: &str) -> Result<Branch<'r>, String> {
+    repo.find_branch(name, BranchType::Local)
+        .map_err(|_| format!("Branch '{}' not found", name))
+}
+
+/// A local branch `name`, or `name` on any remote
+pub(crate) fn branch_exists(repo: &Repository, name: &str) -> bool {
+    repo.find_branch(name, BranchType::Local).is_ok()
+        || repo
+            .branches(Some(BranchType::Remote))
+            .map(|branches| {
+                branches.flatten().any(|(b, _)| {
+                    b.name()
+                        .ok()
+                        .flatten()
+                        .and_then(|n| n.split_once('/'))
+                        .is_some_and(|(_, n)| n == name)
+                })
+            })
+            .unwrap_or(false)
+}
+
+fn branch_tip(branch: &Branch) -> Result<Oid, String> {
+    branch
+        .get()
+        .target()
+        .ok_or_else(|| "Branch has no target".to_string())
+}
+
+/// Commits reachable from `from` but not from `exclude`, newest first
+fn commits_between(
+    repo: &Repository,
+    from: Oid,
+    exclude: Oid,
+    limit: usize,
+) -> Result<Vec<CommitInfo>, String> {
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    revwalk.push(from).map_err(|e| e.to_string())?;
+    revwalk.hide(exclude).map_err(|e| e.to_string())?;
+    revwalk
+        .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .map_err(|e| e.to_string())?;
+
+    let mut commits = Vec::new();
+    for id in revwalk.take(limit) {
+        let id = id.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(id).map_err(|e| e.to_string())?;
+        let time = Utc
+            .timestamp_opt(commit.time().seconds(), 0)
+            .single()
+            .unwrap_or(Utc::now());
+        commits.push(CommitInfo {
+            id: id.to_string().chars().take(7).collect(),
+            author: commit.author().name().unwrap_or("Unknown").to_string(),
+            time: time.format("%Y-%m-%d %H:%M").to_string(),
+            message: commit.message().unwrap_or("").trim().to_string(),
+        });
+    }
+    Ok(commits)
+}
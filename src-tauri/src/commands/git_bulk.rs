@@ -0,0 +1,214 @@
+use crate::commands::git_branches::branch_exists;
+use crate::commands::repo::group_repositories;
+use crate::core::credentials::redact;
+use crate::core::git_remote::GitRemote;
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use git2::Repository;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tauri::{Emitter, State};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+const DEFAULT_CONCURRENCY: usize = 4;
+const MAX_CONCURRENCY: usize = 16;
+
+/// Operations that can run across a group; resets are left to single repositories
+const BULK_OPERATIONS: &[&str] = &["fetch", "pull", "push", "checkout"];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BulkRepoResult {
+    pub repo_id: String,
+    pub name: String,
+    pub status: String, // "ok" | "skipped" | "failed"
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct BulkGitProgress {
+    pub operation: String,
+    pub repo_id: String,
+    pub name: String,
+    pub status: String, // "running" | "ok" | "skipped" | "failed"
+    pub message: Option<String>,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BulkGitReport {
+    pub operation: String,
+    pub results: Vec<BulkRepoResult>,
+    /// A failure stopped the run; repositories not yet started were skipped
+    pub stopped: bool,
+}
+
+/// Run `operation` (`fetch`, `pull`, `push` or `checkout`) on every repository in a group,
+/// `concurrency` at a time. `arg` is passed on as for task steps (remote, branch).
+/// Repositories the operation does not apply to are skipped rather than failed.
+/// With `stop_on_error` no new repository is started after the first failure.
+/// Progress is reported through `bulk_git_progress` events.
+#[tauri::command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
+pub async fn bulk_git_operation(
+    group_id: String,
+    operation: String,
+    arg: Option<String>,
+    recursive: Option<bool>,
+    rebase: Option<bool>,
+    concurrency: Option<usize>,
+    stop_on_error: Option<bool>,
+    window: tauri::Window,
+    state: State<'_, DatabaseManager>,
+) -> Result<BulkGitReport, String> {
+    if !BULK_OPERATIONS.contains(&operation.as_str()) {
+        return Err(format!("{} cannot be run across a group", operation));
+    }
+    let arg = arg.map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+    if operation == "checkout" && arg.is_none() {
+        return Err("checkout needs a branch".to_string());
+    }
+
+    let repos = group_repositories(&state.connection, &group_id, recursive.unwrap_or(true)).await?;
+    let total = repos.len();
+    let permits = Arc::new(Semaphore::new(
+        concurrency
+            .unwrap_or(DEFAULT_CONCURRENCY)
+            .clamp(1, MAX_CONCURRENCY),
+    ));
+    let stop_on_error = stop_on_error.unwrap_or(false);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let rebase = rebase.unwrap_or(false);
+
+    let mut tasks = JoinSet::new();
+    for (index, repo) in repos.into_iter().enumerate() {
+        let permits = permits.clone();
+        let stopped = stopped.clone();
+        let completed = completed.clone();
+        let window = window.clone();
+        let operation = operation.clone();
+        let arg = arg.clone();
+
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let emit = |status: &str, message: Option<String>, completed: usize| {
+                let _ = window.emit(
+                    "bulk_git_progress",
+                    BulkGitProgress {
+                        operation: operation.clone(),
+                        repo_id: repo.id.clone(),
+                        name: repo.name.clone(),
+                        status: status.to_string(),
+                        message,
+                        completed,
+                        total,
+                    },
+                );
+            };
+
+            let (status, message) = if stopped.load(Ordering::SeqCst) {
+                (
+                    "skipped",
+                    Some("Stopped after an earlier failure".to_string()),
+                )
+            } else {
+                emit("running", None, completed.load(Ordering::SeqCst));
+                let model = repo.clone();
+                let op = operation.clone();
+                let outcome = tokio::task::spawn_blocking(move || {
+                    run_on_repository(&model, &op, arg.as_deref(), rebase)
+                })
+                .await
+                .unwrap_or_else(|e| Outcome::Failed(e.to_string()));
+                match outcome {
+                    Outcome::Ok(message) => ("ok", Some(message)),
+                    Outcome::Skipped(reason) => ("skipped", Some(reason)),
+                    Outcome::Failed(error) => {
+                        if stop_on_error {
+                            stopped.store(true, Ordering::SeqCst);
+                        }
+                        ("failed", Some(redact(&error)))
+                    }
+                }
+            };
+
+            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            emit(status, message.clone(), done);
+            (
+                index,
+                BulkRepoResult {
+                    repo_id: repo.id.clone(),
+                    name: repo.name.clone(),
+                    status: status.to_string(),
+                    message,
+                },
+            )
+        });
+    }
+
+    let mut results = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined.map_err(|e| e.to_string())?);
+    }
+    results.sort_by_key(|(index, _)| *index);
+
+    Ok(BulkGitReport {
+        operation,
+        results: results.into_iter().map(|(_, result)| result).collect(),
+        stopped: stopped.load(Ordering::SeqCst),
+    })
+}
+
+enum Outcome {
+    Ok(String),
+    Skipped(String),
+    Failed(String),
+}
+
+fn run_on_repository(
+    model: &repositories::Model,
+    operation: &str,
+    arg: Option<&str>,
+    rebase: bool,
+) -> Outcome {
+    if !Path::new(&model.local_path).exists() {
+        return Outcome::Skipped("Local path does not exist".to_string());
+    }
+    match Repository::open(&model.local_path) {
+        Ok(repo) => {
+            if let Some(reason) = skip_reason(&repo, operation, arg) {
+                return Outcome::Skipped(reason);
+            }
+        }
+        Err(e) => return Outcome::Skipped(format!("Not a git repository: {}", e)),
+    }
+
+    match GitRemote::open(model).and_then(|remote| remote.run(operation, arg, false, rebase)) {
+        Ok(result) => Outcome::Ok(result.message),
+        Err(e) => Outcome::Failed(e.to_string()),
+    }
+}
+
+/// Why `operation` does not apply to `repo`, if it does not
+fn skip_reason(repo: &Repository, operation: &str, arg: Option<&str>) -> Option<String> {
+    let has_remote = repo.remotes().map(|r| !r.is_empty()).unwrap_or(false);
+    let head = repo.head().ok();
+    match operation {
+        "fetch" | "pull" | "push" if !has_remote => Some("No remote configured".to_string()),
+        "pull" | "push" if arg.is_none() && !head.as_ref().is_some_and(|h| h.is_branch()) => {
+            Some("HEAD is not on a branch".to_string())
+        }
+        "checkout" => {
+            let branch = arg.unwrap_or_default();
+            if head.as_ref().and_then(|h| h.shorthand()) == Some(branch) {
+                return Some(format!("Already on {}", branch));
+            }
+            (!branch_exists(repo, branch)).then(|| format!("No branch {}", branch))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,438 @@
+use crate::commands::git_ops::open_repo;
+use crate::database::entities::settings;
+use crate::database::manager::DatabaseManager;
+use git2::{
+    build::CheckoutBuilder, ApplyLocation, ApplyOptions, Diff, DiffOptions, ErrorCode,
+    IndexAddOption, Repository, Signature, Status, StatusOptions,
+};
+use sea_orm::EntityTrait;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::path::{Component, Path, PathBuf};
+use tauri::State;
+
+/// Settings holding the identity used for commits made from SourceBridge;
+/// the repository's git config is used when they are empty
+pub const GIT_AUTHOR_NAME_SETTING: &str = "git_author_name";
+pub const GIT_AUTHOR_EMAIL_SETTING: &str = "git_author_email";
+
+#[derive(Serialize, Debug)]
+pub struct FileChange {
+    pub path: String,
+    /// Previous path for renames
+    pub old_path: Option<String>,
+    pub status: String, // "added" | "modified" | "deleted" | "renamed" | "typechange"
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct WorkingTreeChanges {
+    pub staged: Vec<FileChange>,
+    pub unstaged: Vec<FileChange>,
+    pub untracked: Vec<String>,
+    pub conflicted: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DiffHunk {
+    pub index: usize,
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Lines prefixed with ' ', '+' or '-'
+    pub lines: Vec<String>,
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_working_tree_changes(path: String) -> Result<WorkingTreeChanges, String> {
+    let repo = open_repo(&path)?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true);
+    let statuses = repo.statuses(Some(&mut opts)).map_err(|e| e.to_string())?;
+
+    let mut changes = WorkingTreeChanges::default();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        let Some(path) = entry.path().map(String::from) else {
+            continue;
+        };
+
+        if status.is_conflicted() {
+            changes.conflicted.push(path);
+            continue;
+        }
+        if status.is_wt_new() {
+            changes.untracked.push(path.clone());
+        }
+
+        let staged = if status.is_index_new() {
+            Some("added")
+        } else if status.is_index_modified() {
+            Some("modified")
+        } else if status.is_index_deleted() {
+            Some("deleted")
+        } else if status.is_index_renamed() {
+            Some("renamed")
+        } else if status.is_index_typechange() {
+            Some("typechange")
+        } else {
+            None
+        };
+        if let Some(kind) = staged {
+            let old_path = entry
+                .head_to_index()
+                .and_then(|d| d.old_file().path().map(|p| p.to_string_lossy().to_string()))
+                .filter(|p| *p != path);
+            changes.staged.push(FileChange {
+                path: path.clone(),
+                old_path,
+                status: kind.to_string(),
+            });
+        }
+
+        let unstaged = if status.is_wt_modified() {
+            Some("modified")
+        } else if status.is_wt_deleted() {
+            Some("deleted")
+        } else if status.is_wt_typechange() {
+            Some("typechange")
+        } else {
+            None
+        };
+        if let Some(kind) = unstaged {
+            changes.unstaged.push(FileChange {
+                path,
+                old_path: None,
+                status: kind.to_string(),
+            });
+        }
+    }
+    Ok(changes)
+}
+
+/// Stage files or directories, including deletions
+#[tauri::command(rename_all = "snake_case")]
+pub fn stage_git_paths(path: String, paths: Vec<String>) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    index
+        .add_all(paths.iter(), IndexAddOption::DEFAULT, None)
+        .map_err(|e| format!("Failed to stage: {}", e))?;
+    // add_all never removes entries; update_all stages deletions of tracked files
+    index
+        .update_all(paths.iter(), None)
+        .map_err(|e| format!("Failed to stage: {}", e))?;
+    index.write().map_err(|e| e.to_string())
+}
+
+/// Reset the index entries of `paths` to HEAD, keeping the working tree
+#[tauri::command(rename_all = "snake_case")]
+pub fn unstage_git_paths(path: String, paths: Vec<String>) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let head = repo.head().and_then(|h| h.peel_to_commit());
+    match head {
+        Ok(head) => repo
+            .reset_default(Some(head.as_object()), paths.iter())
+            .map_err(|e| format!("Failed to unstage: {}", e)),
+        // No commits yet: unstaging means removing from the index
+        Err(e) if e.code() == ErrorCode::UnbornBranch => {
+            let mut index = repo.index().map_err(|e| e.to_string())?;
+            index
+                .remove_all(paths.iter(), None)
+                .map_err(|e| format!("Failed to unstage: {}", e))?;
+            index.write().map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Hunks of one file: staged (HEAD to index) or unstaged (index to working tree)
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_file_hunks(path: String, file: String, staged: bool) -> Result<Vec<DiffHunk>, String> {
+    let repo = open_repo(&path)?;
+    let diff = file_diff(&repo, &file, staged, false)?;
+
+    // Both callbacks need the list, so share it through a RefCell
+    let hunks: RefCell<Vec<DiffHunk>> = RefCell::new(Vec::new());
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        Some(&mut |_, hunk| {
+            let mut hunks = hunks.borrow_mut();
+            let index = hunks.len();
+            hunks.push(DiffHunk {
+                index,
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines: Vec::new(),
+            });
+            true
+        }),
+        Some(&mut |_, _, line| {
+            if let (Some(hunk), '+' | '-' | ' ') = (hunks.borrow_mut().last_mut(), line.origin()) {
+                hunk.lines.push(format!(
+                    "{}{}",
+                    line.origin(),
+                    String::from_utf8_lossy(line.content()).trim_end_matches('\n')
+                ));
+            }
+            true
+        }),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(hunks.into_inner())
+}
+
+/// Stage the given unstaged hunks of a file (indexes from `get_file_hunks` with `staged = false`)
+#[tauri::command(rename_all = "snake_case")]
+pub fn stage_git_hunks(path: String, file: String, hunks: Vec<usize>) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let diff = file_diff(&repo, &file, false, false)?;
+    apply_hunks(&repo, &diff, &hunks)
+}
+
+/// Unstage the given staged hunks of a file (indexes from `get_file_hunks` with `staged = true`)
+#[tauri::command(rename_all = "snake_case")]
+pub fn unstage_git_hunks(path: String, file: String, hunks: Vec<usize>) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    // Applying the reversed staged diff to the index takes the hunks back out
+    let diff = file_diff(&repo, &file, true, true)?;
+    apply_hunks(&repo, &diff, &hunks)
+}
+
+/// Throw away unstaged changes: tracked files are restored from the index, untracked files deleted.
+/// A directory discards every change below it; ignored files are left alone.
+#[tauri::command(rename_all = "snake_case")]
+pub fn discard_git_changes(path: String, paths: Vec<String>) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no working tree")?
+        .to_path_buf();
+
+    let mut untracked = Vec::new();
+    let mut dirs = Vec::new();
+    let mut tracked = Vec::new();
+    for file in &paths {
+        let full = workdir_path(&workdir, file)?;
+        let mut opts = StatusOptions::new();
+        opts.pathspec(file)
+            .disable_pathspec_match(true)
+            .include_untracked(true)
+            .recurse_untracked_dirs(true);
+        let statuses = repo
+            .statuses(Some(&mut opts))
+            .map_err(|e| format!("Failed to read status of {}: {}", file, e))?;
+        for entry in statuses.iter() {
+            let Some(entry_path) = entry.path() else {
+                continue;
+            };
+            if entry.status() == Status::WT_NEW {
+                untracked.push(workdir.join(entry_path));
+            } else if entry.status().intersects(
+                Status::WT_MODIFIED
+                    | Status::WT_DELETED
+                    | Status::WT_TYPECHANGE
+                    | Status::WT_RENAMED,
+            ) {
+                tracked.push(entry_path.to_string());
+            }
+        }
+        if full.is_dir() {
+            collect_dirs(&full, &mut dirs);
+        }
+    }
+
+    for full in &untracked {
+        std::fs::remove_file(full)
+            .map_err(|e| format!("Failed to delete {}: {}", full.display(), e))?;
+    }
+    // Directories emptied by the deletions above; ones still holding ignored files stay
+    for dir in dirs.iter().rev() {
+        let _ = std::fs::remove_dir(dir);
+    }
+
+    if !tracked.is_empty() {
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force();
+        for file in &tracked {
+            checkout.path(file);
+        }
+        repo.checkout_index(None, Some(&mut checkout))
+            .map_err(|e| format!("Failed to discard changes: {}", e))?;
+    }
+    Ok(())
+}
+
+/// `file` resolved inside `workdir`; absolute paths, `..` and symlinks leading out are rejected
+fn workdir_path(workdir: &Path, file: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(file);
+    let plain = relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if file.trim().is_empty() || !plain {
+        return Err(format!("{} is not a path inside the repository", file));
+    }
+    let full = workdir.join(relative);
+    // The file itself may be gone (a tracked deletion); its nearest existing parent must resolve inside
+    let root = workdir.canonicalize().map_err(|e| e.to_string())?;
+    let existing = full.ancestors().find(|p| p.exists()).unwrap_or(workdir);
+    let resolved = existing.canonicalize().map_err(|e| e.to_string())?;
+    if !resolved.starts_with(&root) {
+        return Err(format!("{} is not a path inside the repository", file));
+    }
+    Ok(full)
+}
+
+/// Collect `dir` and its subdirectories (not following symlinks), parents before children
+fn collect_dirs(dir: &Path, out: &mut Vec<PathBuf>) {
+    out.push(dir.to_path_buf());
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) && !path.ends_with(".git") {
+                collect_dirs(&path, out);
+            }
+        }
+    }
+}
+
+/// Commit the staged changes and return the new commit SHA.
+/// The author is taken from the `git_author_name`/`git_author_email` settings, then git config.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn commit_git_changes(
+    path: String,
+    message: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<String, String> {
+    if message.trim().is_empty() {
+        return Err("Commit message is empty".to_string());
+    }
+    let identity = configured_identity(&state).await?;
+
+    let repo = open_repo(&path)?;
+    let signature = commit_signature(&repo, &identity)?;
+
+    create_commit(&repo, &signature, &message)
+}
+
+/// Commit the index on top of HEAD (or as the first commit)
+pub(crate) fn create_commit(
+    repo: &Repository,
+    signature: &Signature,
+    message: &str,
+) -> Result<String, String> {
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    if index.has_conflicts() {
+        return Err("Resolve merge conflicts before committing".to_string());
+    }
+    let tree_id = index.write_tree().map_err(|e| e.to_string())?;
+    let tree = repo.find_tree(tree_id).map_err(|e| e.to_string())?;
+
+    let parent = match repo.head().and_then(|h| h.peel_to_commit()) {
+        Ok(commit) => Some(commit),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e.to_string()),
+    };
+    let unchanged = match &parent {
+        Some(parent) => parent.tree_id() == tree_id,
+        None => tree.is_empty(),
+    };
+    if unchanged {
+        return Err("Nothing staged to commit".to_string());
+    }
+
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let oid = repo
+        .commit(
+            Some("HEAD"),
+            signature,
+            signature,
+            message.trim(),
+            &tree,
+            &parents,
+        )
+        .map_err(|e| format!("Failed to commit: {}", e))?;
+    Ok(oid.to_string())
+}
+
+fn file_diff<'r>(
+    repo: &'r Repository,
+    file: &str,
+    staged: bool,
+    reverse: bool,
+) -> Result<Diff<'r>, String> {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(file)
+        .disable_pathspec_match(true)
+        .reverse(reverse);
+    let diff = if staged {
+        let head_tree = match repo.head().and_then(|h| h.peel_to_tree()) {
+            Ok(tree) => Some(tree),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+            Err(e) => return Err(e.to_string()),
+        };
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))
+    } else {
+        repo.diff_index_to_workdir(None, Some(&mut opts))
+    };
+    diff.map_err(|e| e.to_string())
+}
+
+fn apply_hunks(repo: &Repository, diff: &Diff, hunks: &[usize]) -> Result<(), String> {
+    if hunks.is_empty() {
+        return Ok(());
+    }
+    let mut next = 0;
+    let mut options = ApplyOptions::new();
+    options.hunk_callback(|_| {
+        let selected = hunks.contains(&next);
+        next += 1;
+        selected
+    });
+    repo.apply(diff, ApplyLocation::Index, Some(&mut options))
+        .map_err(|e| format!("Failed to apply hunks: {}", e))
+}
+
+/// Author name and email from settings, when both are configured
+pub(crate) async fn configured_identity(
+    state: &DatabaseManager,
+) -> Result<Option<(String, String)>, String> {
+    let name = setting_value(state, GIT_AUTHOR_NAME_SETTING).await?;
+    let email = setting_value(state, GIT_AUTHOR_EMAIL_SETTING).await?;
+    Ok(name.zip(email))
+}
+
+/// Signature for the configured identity, falling back to the repository's git config
+pub(crate) fn commit_signature(
+    repo: &Repository,
+    identity: &Option<(String, String)>,
+) -> Result<Signature<'static>, String> {
+    match identity {
+        Some((name, email)) => Signature::now(name, email),
+        None => repo.signature(),
+    }
+    .map_err(|e| {
+        format!(
+            "No commit identity; set the author name and email in settings ({})",
+            e
+        )
+    })
+}
+
+async fn setting_value(state: &DatabaseManager, key: &str) -> Result<Option<String>, String> {
+    Ok(settings::Entity::find_by_id(key)
+        .one(&state.connection)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|s| s.value.trim().to_string())
+        .filter(|v| !v.is_empty()))
+}
@@ -1,5 +1,7 @@
+use crate::commands::git_branches::{checkout_local_branch, create_tracking_branch_in};
+use crate::core::git_history::{CommitDetail, GitHistory, HistoryPage, HistoryQuery};
 use chrono::{TimeZone, Utc};
-use git2::{build::CheckoutBuilder, BranchType, Repository};
+use git2::{BranchType, ErrorCode, Repository, Signature, StashFlags};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -18,6 +20,13 @@ pub struct CommitInfo {
     pub message: String,
 }
 
+#[derive(Serialize, Debug)]
+pub struct StashInfo {
+    pub index: usize,
+    pub message: String,
+    pub sha: String,
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub fn get_git_branches(path: String) -> Result<Vec<BranchInfo>, String> {
     let repo_path = Path::new(&path);
@@ -71,51 +80,165 @@ pub fn get_git_branches(path: String) -> Result<Vec<BranchInfo>, String> {
     Ok(branches)
 }
 
+/// Switch to a local branch. Given a remote branch (e.g. `origin/feature`), switches to the
+/// local branch of the same name, creating it to track the remote branch when missing.
+/// With `auto_stash`, local changes are stashed before switching and re-applied afterwards.
 #[tauri::command(rename_all = "snake_case")]
-pub fn switch_git_branch(path: String, branch: String) -> Result<(), String> {
+pub fn switch_git_branch(
+    path: String,
+    branch: String,
+    auto_stash: Option<bool>,
+) -> Result<(), String> {
     let repo_path = Path::new(&path);
-    let repo =
+    let mut repo =
         Repository::open(repo_path).map_err(|e| format!("Failed to open git repo: {}", e))?;
 
-    // Find the branch
-    // This logic handles local branches. If it's a remote branch, we might need to create a local tracking branch.
-    // For now, assuming user switches to an existing local branch or we handle remote checkout simply.
-
-    // First try to find local branch
-    let branch_ref_name = format!("refs/heads/{}", branch);
-    let obj = match repo.revparse_single(&branch_ref_name) {
-        Ok(o) => o,
-        Err(_) => {
-            // Try remote?
-            let remote_ref_name = format!("refs/remotes/{}", branch);
-            repo.revparse_single(&remote_ref_name)
-                .map_err(|e| format!("Branch not found: {}", e))?
+    if !auto_stash.unwrap_or(false) {
+        return switch_branch(&repo, &branch);
+    }
+
+    let stashed = save_stash(
+        &mut repo,
+        Some(&format!(
+            "SourceBridge: auto-stash before switching to {}",
+            branch
+        )),
+        false,
+    )?
+    .is_some();
+
+    if let Err(e) = switch_branch(&repo, &branch) {
+        if stashed {
+            // Put the changes back where they came from
+            pop_stash(&mut repo, 0)
+                .map_err(|pop| format!("{}; restoring stashed changes failed: {}", e, pop))?;
         }
-    };
+        return Err(e);
+    }
+
+    if stashed {
+        pop_stash(&mut repo, 0).map_err(|e| {
+            format!(
+                "Switched to {} but re-applying the stashed changes failed: {}",
+                branch, e
+            )
+        })?;
+    }
+    Ok(())
+}
+
+fn switch_branch(repo: &Repository, branch: &str) -> Result<(), String> {
+    if repo.find_branch(branch, BranchType::Local).is_ok() {
+        return checkout_local_branch(repo, branch);
+    }
+
+    if repo.find_branch(branch, BranchType::Remote).is_err() {
+        return Err(format!("Branch not found: {}", branch));
+    }
+    let local_name = branch
+        .split_once('/')
+        .map(|(_, rest)| rest.to_string())
+        .ok_or_else(|| format!("Branch not found: {}", branch))?;
+    if repo.find_branch(&local_name, BranchType::Local).is_err() {
+        create_tracking_branch_in(repo, branch, Some(&local_name))?;
+    }
+    checkout_local_branch(repo, &local_name)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn list_git_stashes(path: String) -> Result<Vec<StashInfo>, String> {
+    let mut repo = open_repo(&path)?;
+    let mut stashes = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        stashes.push(StashInfo {
+            index,
+            message: message.to_string(),
+            sha: oid.to_string(),
+        });
+        true
+    })
+    .map_err(|e| e.to_string())?;
+    Ok(stashes)
+}
+
+/// Stash local changes; returns the stash commit, or `None` when there was nothing to stash
+#[tauri::command(rename_all = "snake_case")]
+pub fn stash_git_changes(
+    path: String,
+    message: Option<String>,
+    include_untracked: Option<bool>,
+) -> Result<Option<String>, String> {
+    let mut repo = open_repo(&path)?;
+    save_stash(
+        &mut repo,
+        message.as_deref().filter(|m| !m.is_empty()),
+        include_untracked.unwrap_or(false),
+    )
+}
 
-    // Checkout
-    // We need to detach head or set head to the branch ref.
-    // If it is a local branch, we want to set HEAD to refs/heads/branchName
-    // If remote, we usually checkout as detached HEAD or create a new branch.
+#[tauri::command(rename_all = "snake_case")]
+pub fn apply_git_stash(path: String, index: usize) -> Result<(), String> {
+    let mut repo = open_repo(&path)?;
+    repo.stash_apply(index, None)
+        .map_err(|e| format!("Failed to apply stash: {}", e))
+}
 
-    // Simplification: Check if it's a local branch, if so set HEAD.
-    // If not, maybe just error for now (Plan says "Switch branch", usually implies local).
-    // Let's assume input `branch` is the short name of a local branch for now, or full name.
+/// Apply a stash and drop it when it applied cleanly
+#[tauri::command(rename_all = "snake_case")]
+pub fn pop_git_stash(path: String, index: usize) -> Result<(), String> {
+    let mut repo = open_repo(&path)?;
+    pop_stash(&mut repo, index)
+}
 
-    // Safe switch
-    repo.checkout_tree(&obj, Some(CheckoutBuilder::new().safe()))
-        .map_err(|e| format!("Checkout failed (conflict?): {}", e))?;
+#[tauri::command(rename_all = "snake_case")]
+pub fn drop_git_stash(path: String, index: usize) -> Result<(), String> {
+    let mut repo = open_repo(&path)?;
+    repo.stash_drop(index)
+        .map_err(|e| format!("Failed to drop stash: {}", e))
+}
 
-    if repo.find_branch(&branch, BranchType::Local).is_ok() {
-        repo.set_head(&branch_ref_name)
-            .map_err(|e| format!("Failed to set HEAD: {}", e))?;
+fn save_stash(
+    repo: &mut Repository,
+    message: Option<&str>,
+    include_untracked: bool,
+) -> Result<Option<String>, String> {
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("SourceBridge", "sourcebridge@localhost"))
+        .map_err(|e| e.to_string())?;
+    let flags = if include_untracked {
+        StashFlags::INCLUDE_UNTRACKED
     } else {
-        // Detached head for commit/remote?
-        repo.set_head_detached(obj.id())
-            .map_err(|e| format!("Failed to detach HEAD: {}", e))?;
+        StashFlags::DEFAULT
+    };
+
+    match repo.stash_save2(&signature, message, Some(flags)) {
+        Ok(oid) => Ok(Some(oid.to_string())),
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to stash changes: {}", e)),
     }
+}
 
-    Ok(())
+/// libgit2 drops the stash even when applying it left conflicts, so apply and drop separately
+fn pop_stash(repo: &mut Repository, index: usize) -> Result<(), String> {
+    repo.stash_apply(index, None)
+        .map_err(|e| format!("Failed to apply stash: {}", e))?;
+    let conflicted = repo
+        .index()
+        .map(|i| i.has_conflicts())
+        .map_err(|e| e.to_string())?;
+    if conflicted {
+        return Err(
+            "Stashed changes conflict with the working tree; resolve the conflicts, the stash is kept"
+                .to_string(),
+        );
+    }
+    repo.stash_drop(index)
+        .map_err(|e| format!("Failed to drop stash: {}", e))
+}
+
+pub(crate) fn open_repo(path: &str) -> Result<Repository, String> {
+    Repository::open(Path::new(path)).map_err(|e| format!("Failed to open git repo: {}", e))
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -174,6 +297,24 @@ pub fn get_git_log(path: String, count: i32) -> Result<Vec<CommitInfo>, String>
     Ok(commits)
 }
 
+/// Paginated, filterable commit history with full SHAs, parents, ISO times and file stats
+#[tauri::command(rename_all = "snake_case")]
+pub fn query_git_history(path: String, query: Option<HistoryQuery>) -> Result<HistoryPage, String> {
+    let repo = open_repo(&path)?;
+    GitHistory::query(&repo, &query.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// A commit's metadata, changed files with line counts and the unified diff of each file
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_commit_detail(
+    path: String,
+    sha: String,
+    context_lines: Option<u32>,
+) -> Result<CommitDetail, String> {
+    let repo = open_repo(&path)?;
+    GitHistory::commit_detail(&repo, &sha, context_lines).map_err(|e| e.to_string())
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub fn open_in_folder(path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
@@ -0,0 +1,36 @@
+use crate::core::credentials::redact;
+use crate::core::git_remote::{GitOpResult, GitRemote};
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use sea_orm::EntityTrait;
+use tauri::State;
+
+/// Run fetch/pull/push/reset/reset_remote/checkout on a registered repository with its stored
+/// credentials. `force_with_lease` applies to push, `rebase` to pull.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn run_git_operation(
+    repo_id: String,
+    operation: String,
+    arg: Option<String>,
+    force_with_lease: Option<bool>,
+    rebase: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<GitOpResult, String> {
+    let repo = repositories::Entity::find_by_id(&repo_id)
+        .one(&state.connection)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Repository not found".to_string())?;
+
+    tokio::task::spawn_blocking(move || {
+        GitRemote::open(&repo)?.run(
+            &operation,
+            arg.as_deref(),
+            force_with_lease.unwrap_or(false),
+            rebase.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| redact(&e.to_string()))
+}
@@ -0,0 +1,311 @@
+use crate::commands::git_changes::{commit_signature, configured_identity};
+use crate::commands::git_ops::open_repo;
+use crate::commands::repo::{find_repository, group_repositories};
+use crate::core::credentials::redact;
+use crate::core::git_history::format_git_time;
+use crate::core::git_remote::{GitOpResult, GitRemote};
+use crate::database::manager::DatabaseManager;
+use git2::{Oid, Repository, Signature, StatusOptions};
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Serialize, Debug)]
+pub struct TagInfo {
+    pub name: String,
+    /// Commit the tag points to
+    pub target: String,
+    /// Tag object for annotated tags
+    pub tag_sha: Option<String>,
+    pub annotated: bool,
+    pub message: Option<String>,
+    pub tagger: Option<String>,
+    pub tagged_at: Option<String>,
+    pub commit_time: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RepoTagResult {
+    pub repo_id: String,
+    pub name: String,
+    pub status: String, // "ready" | "blocked" | "tagged" | "pushed" | "rolled_back" | "failed"
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GroupTagReport {
+    pub tag: String,
+    /// Every repository passed the checks and was tagged (and pushed when asked).
+    /// When tagging fails part way the tags made so far are removed again; when a
+    /// push fails the other repositories keep their tags and `results` says which.
+    pub applied: bool,
+    pub results: Vec<RepoTagResult>,
+}
+
+/// Tags with their target commits and annotations, newest commit first
+#[tauri::command(rename_all = "snake_case")]
+pub fn list_git_tags(path: String) -> Result<Vec<TagInfo>, String> {
+    let repo = open_repo(&path)?;
+    let mut refs = Vec::new();
+    repo.tag_foreach(|oid, name| {
+        let name = String::from_utf8_lossy(name);
+        refs.push((oid, name.trim_start_matches("refs/tags/").to_string()));
+        true
+    })
+    .map_err(|e| e.to_string())?;
+
+    let mut tags: Vec<(i64, TagInfo)> = Vec::new();
+    for (oid, name) in refs {
+        let object = repo.find_object(oid, None).map_err(|e| e.to_string())?;
+        // Tags on trees or blobs are rare and have no commit to show
+        let Ok(commit) = object.peel_to_commit() else {
+            continue;
+        };
+        let annotation = object.as_tag();
+        tags.push((
+            commit.time().seconds(),
+            TagInfo {
+                name,
+                target: commit.id().to_string(),
+                tag_sha: annotation.map(|t| t.id().to_string()),
+                annotated: annotation.is_some(),
+                message: annotation.and_then(|t| t.message().map(|m| m.trim().to_string())),
+                tagger: annotation
+                    .and_then(|t| t.tagger())
+                    .and_then(|s| s.name().map(String::from)),
+                tagged_at: annotation
+                    .and_then(|t| t.tagger())
+                    .map(|s| format_git_time(s.when())),
+                commit_time: format_git_time(commit.time()),
+            },
+        ));
+    }
+
+    tags.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    Ok(tags.into_iter().map(|(_, tag)| tag).collect())
+}
+
+/// Create a tag at `target` (default HEAD). With a `message` the tag is annotated,
+/// otherwise lightweight. Returns the tag object (annotated) or commit (lightweight) SHA.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn create_git_tag(
+    path: String,
+    name: String,
+    target: Option<String>,
+    message: Option<String>,
+    force: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<String, String> {
+    let identity = configured_identity(&state).await?;
+    let repo = open_repo(&path)?;
+    let signature = commit_signature(&repo, &identity)?;
+    let oid = tag_repository(
+        &repo,
+        &name,
+        target.as_deref(),
+        message.as_deref(),
+        force.unwrap_or(false),
+        &signature,
+    )?;
+    Ok(oid.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn delete_git_tag(path: String, name: String) -> Result<(), String> {
+    let repo = open_repo(&path)?;
+    repo.tag_delete(&name)
+        .map_err(|e| format!("Failed to delete tag: {}", e))
+}
+
+/// Push tags (all when `tags` is empty) to `remote` (default origin), or delete them there with `delete`
+#[tauri::command(rename_all = "snake_case")]
+pub async fn push_git_tags(
+    repo_id: String,
+    tags: Option<Vec<String>>,
+    remote: Option<String>,
+    delete: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<GitOpResult, String> {
+    let repo = find_repository(&state.connection, &repo_id).await?;
+    tokio::task::spawn_blocking(move || {
+        GitRemote::open(&repo)?.push_tags(
+            remote.as_deref().filter(|r| !r.is_empty()),
+            &tags.unwrap_or_default(),
+            delete.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| redact(&e.to_string()))
+}
+
+/// Apply the same tag to HEAD of every repository in a group.
+/// Nothing is tagged unless every repository is clean, on a branch, and has no unpushed commits;
+/// the report then lists what blocked it. With `push` the tag is pushed to each repository's remote.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn tag_repo_group(
+    group_id: String,
+    tag: String,
+    message: Option<String>,
+    recursive: Option<bool>,
+    push: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<GroupTagReport, String> {
+    let db = &state.connection;
+    if tag.trim().is_empty() {
+        return Err("Tag name is empty".to_string());
+    }
+
+    let repos = group_repositories(db, &group_id, recursive.unwrap_or(true)).await?;
+    if repos.is_empty() {
+        return Err("The group has no repositories".to_string());
+    }
+    let identity = configured_identity(&state).await?;
+
+    tokio::task::spawn_blocking(move || {
+        // 1. Check every repository before touching any of them
+        let mut results: Vec<RepoTagResult> = repos
+            .iter()
+            .map(|repo| {
+                let problem = release_blocker(&repo.local_path, &tag).err();
+                RepoTagResult {
+                    repo_id: repo.id.clone(),
+                    name: repo.name.clone(),
+                    status: if problem.is_some() {
+                        "blocked"
+                    } else {
+                        "ready"
+                    }
+                    .to_string(),
+                    message: problem,
+                }
+            })
+            .collect();
+        if results.iter().any(|r| r.status == "blocked") {
+            return GroupTagReport {
+                tag,
+                applied: false,
+                results,
+            };
+        }
+
+        // 2. Tag every repository, taking back this run's tags if any of them fails
+        let mut failed = false;
+        for (repo, result) in repos.iter().zip(results.iter_mut()) {
+            let outcome = open_repo(&repo.local_path).and_then(|git_repo| {
+                let signature = commit_signature(&git_repo, &identity)?;
+                tag_repository(&git_repo, &tag, None, message.as_deref(), false, &signature)
+            });
+            match outcome {
+                Ok(_) => result.status = "tagged".to_string(),
+                Err(e) => {
+                    result.status = "failed".to_string();
+                    result.message = Some(e);
+                    failed = true;
+                    break;
+                }
+            }
+        }
+        if failed {
+            for (repo, result) in repos.iter().zip(results.iter_mut()) {
+                if result.status != "tagged" {
+                    continue;
+                }
+                match open_repo(&repo.local_path)
+                    .and_then(|r| r.tag_delete(&tag).map_err(|e| e.to_string()))
+                {
+                    Ok(()) => result.status = "rolled_back".to_string(),
+                    Err(e) => result.message = Some(format!("Tag could not be removed: {}", e)),
+                }
+            }
+            return GroupTagReport {
+                tag,
+                applied: false,
+                results,
+            };
+        }
+
+        // 3. Push the tags; a failed push leaves that repository tagged locally
+        if push.unwrap_or(false) {
+            for (repo, result) in repos.iter().zip(results.iter_mut()) {
+                match GitRemote::open(repo)
+                    .and_then(|r| r.push_tags(None, std::slice::from_ref(&tag), false))
+                {
+                    Ok(pushed) => {
+                        result.status = "pushed".to_string();
+                        result.message = Some(pushed.message);
+                    }
+                    Err(e) => {
+                        result.status = "failed".to_string();
+                        result.message = Some(redact(&format!("Tagged but push failed: {}", e)));
+                    }
+                }
+            }
+        }
+
+        GroupTagReport {
+            applied: results.iter().all(|r| r.status != "failed"),
+            tag,
+            results,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn tag_repository(
+    repo: &Repository,
+    name: &str,
+    target: Option<&str>,
+    message: Option<&str>,
+    force: bool,
+    signature: &Signature,
+) -> Result<Oid, String> {
+    let target = target.filter(|t| !t.is_empty()).unwrap_or("HEAD");
+    let object = repo
+        .revparse_single(target)
+        .map_err(|e| format!("Cannot resolve '{}': {}", target, e))?;
+
+    match message.map(str::trim).filter(|m| !m.is_empty()) {
+        Some(message) => repo.tag(name, &object, signature, message, force),
+        None => repo.tag_lightweight(name, &object, force),
+    }
+    .map_err(|e| format!("Failed to create tag: {}", e))
+}
+
+/// Why a repository is not ready to be tagged for a release, if anything
+fn release_blocker(path: &str, tag: &str) -> Result<(), String> {
+    let repo = open_repo(path)?;
+    if repo.find_reference(&format!("refs/tags/{}", tag)).is_ok() {
+        return Err(format!("Tag {} already exists", tag));
+    }
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false);
+    let dirty = repo
+        .statuses(Some(&mut opts))
+        .map_err(|e| e.to_string())?
+        .len();
+    if dirty > 0 {
+        return Err(format!("{} uncommitted change(s)", dirty));
+    }
+
+    let head = repo.head().map_err(|e| e.to_string())?;
+    if !head.is_branch() {
+        return Err("HEAD is detached".to_string());
+    }
+    let head_name = head.name().unwrap_or("HEAD").to_string();
+    let upstream = repo
+        .branch_upstream_name(&head_name)
+        .map_err(|_| "Branch has no upstream".to_string())?;
+    let upstream_oid = repo
+        .refname_to_id(upstream.as_str().unwrap_or(""))
+        .map_err(|e| e.to_string())?;
+    let head_oid = head.target().ok_or("HEAD has no commit")?;
+    let (ahead, _) = repo
+        .graph_ahead_behind(head_oid, upstream_oid)
+        .map_err(|e| e.to_string())?;
+    if ahead > 0 {
+        return Err(format!("{} unpushed commit(s)", ahead));
+    }
+    Ok(())
+}
@@ -1,10 +1,27 @@
 pub mod ai;
+pub mod commit_port;
+pub mod credentials;
 pub mod diff;
+pub mod git_branches;
+pub mod git_bulk;
+pub mod git_changes;
 pub mod git_ops;
+pub mod git_remote;
+pub mod git_tags;
+pub mod provenance;
 pub mod repo;
+pub mod repo_clone;
+pub mod repo_health;
+pub mod repo_reconcile;
+pub mod repo_relocate;
 pub mod repo_scan;
 pub mod report;
 pub mod route;
+pub mod route_bundle;
+pub mod route_graph;
+pub mod route_history;
+pub mod route_impact;
+pub mod route_template;
 pub mod settings;
 pub mod status;
 pub mod task;
@@ -0,0 +1,189 @@
+use crate::commands::git_ops::open_repo;
+use crate::commands::route::MappingRule;
+use crate::core::diff::DiffEngine;
+use crate::core::git_history::{format_git_time, GitHistory, HistoryQuery};
+use crate::core::mapping_history::parse_mappings;
+use crate::core::provenance::Provenance;
+use crate::core::repo_routes::find_route;
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use git2::{Commit, ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use sea_orm::EntityTrait;
+use serde::Serialize;
+use tauri::State;
+
+const DEFAULT_TRACE_LIMIT: usize = 20;
+
+#[derive(Serialize, Debug)]
+pub struct SourceCommitRef {
+    pub sha: String,
+    /// Missing when the commit is not in the local source clone
+    pub summary: Option<String>,
+    pub authored_at: Option<String>,
+    /// Source file the traced target file was synced from
+    pub source_file: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProvenanceTrace {
+    pub target_sha: String,
+    pub summary: String,
+    pub committed_at: String,
+    pub route_id: String,
+    /// `None` when the route no longer exists
+    pub route_name: Option<String>,
+    pub source_repo_id: Option<String>,
+    pub source_repo_name: Option<String>,
+    pub source_repo_path: Option<String>,
+    pub source_commits: Vec<SourceCommitRef>,
+}
+
+/// Trace target commits back to their source through provenance trailers.
+/// Without `file` only `revision` (default HEAD) is traced; with `file` every commit touching
+/// it from `revision` back is checked, newest first, and the source file is resolved too.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn trace_provenance(
+    path: String,
+    revision: Option<String>,
+    file: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<ProvenanceTrace>, String> {
+    let db = &state.connection;
+    let file = file
+        .map(|f| f.trim().trim_start_matches("./").replace('\\', "/"))
+        .filter(|f| !f.is_empty());
+
+    // 1. Target commits carrying trailers
+    let found = {
+        let repo = open_repo(&path)?;
+        target_commits(&repo, revision, file.as_deref(), limit)?
+    };
+
+    // 2. Resolve routes and source repositories, then the source commits
+    let mut traces = Vec::new();
+    for (target_sha, summary, committed_at, provenance) in found {
+        let route = find_route(db, &provenance.route_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let source_repo_id = provenance
+            .source_repo_id
+            .clone()
+            .or_else(|| route.as_ref().and_then(|r| r.main_repo_id.clone()));
+        let source_repo = match &source_repo_id {
+            Some(id) => repositories::Entity::find_by_id(id.clone())
+                .one(db)
+                .await
+                .map_err(|e| e.to_string())?,
+            None => None,
+        };
+        let mappings = route
+            .as_ref()
+            .and_then(|r| parse_mappings(r.mappings.as_deref()).ok())
+            .unwrap_or_default();
+
+        // Git objects are not Send; keep them clear of the awaits
+        let source_commits: Vec<SourceCommitRef> = {
+            let source = source_repo
+                .as_ref()
+                .and_then(|r| Repository::open(&r.local_path).ok());
+            provenance
+                .source_commits
+                .iter()
+                .map(|sha| {
+                    let commit = source
+                        .as_ref()
+                        .and_then(|repo| repo.find_commit(Oid::from_str(sha).ok()?).ok());
+                    let source_file = match (&commit, &file) {
+                        (Some(commit), Some(file)) => source_file_for(commit, &mappings, file),
+                        _ => None,
+                    };
+                    SourceCommitRef {
+                        sha: sha.clone(),
+                        summary: commit
+                            .as_ref()
+                            .map(|c| c.summary().unwrap_or("").to_string()),
+                        authored_at: commit.as_ref().map(|c| format_git_time(c.author().when())),
+                        source_file,
+                    }
+                })
+                .collect()
+        };
+
+        traces.push(ProvenanceTrace {
+            target_sha,
+            summary,
+            committed_at,
+            route_id: provenance.route_id,
+            route_name: route.map(|r| r.name),
+            source_repo_id,
+            source_repo_name: source_repo.as_ref().map(|r| r.name.clone()),
+            source_repo_path: source_repo.map(|r| r.local_path),
+            source_commits,
+        });
+    }
+    Ok(traces)
+}
+
+/// (sha, summary, committed_at, provenance) of the commits to trace
+fn target_commits(
+    repo: &Repository,
+    revision: Option<String>,
+    file: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<(String, String, String, Provenance)>, String> {
+    let Some(file) = file else {
+        let revision = revision.filter(|r| !r.is_empty()).unwrap_or("HEAD".into());
+        let commit = repo
+            .revparse_single(&revision)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|e| format!("Cannot resolve '{}': {}", revision, e))?;
+        return Ok(Provenance::parse(commit.message().unwrap_or(""))
+            .map(|p| {
+                vec![(
+                    commit.id().to_string(),
+                    commit.summary().unwrap_or("").to_string(),
+                    format_git_time(commit.committer().when()),
+                    p,
+                )]
+            })
+            .unwrap_or_default());
+    };
+
+    let query = HistoryQuery {
+        reference: revision,
+        path: Some(file.to_string()),
+        limit: Some(limit.unwrap_or(DEFAULT_TRACE_LIMIT)),
+        ..Default::default()
+    };
+    let page = GitHistory::query(repo, &query).map_err(|e| e.to_string())?;
+    Ok(page
+        .commits
+        .into_iter()
+        .filter_map(|c| {
+            let provenance = Provenance::parse(&c.message)?;
+            Some((c.sha, c.summary, c.committed_at, provenance))
+        })
+        .collect())
+}
+
+/// The file in the source commit whose mapped target path is `target_file`
+fn source_file_for(commit: &Commit, mappings: &[MappingRule], target_file: &str) -> Option<String> {
+    let tree = commit.tree().ok()?;
+    let mut found = None;
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() != Some(ObjectType::Blob) {
+            return TreeWalkResult::Ok;
+        }
+        let path = format!("{}{}", dir, entry.name().unwrap_or(""));
+        match DiffEngine::map_path(mappings, &path) {
+            Ok(Some((_, mapped))) if mapped == target_file => {
+                found = Some(path);
+                TreeWalkResult::Abort
+            }
+            _ => TreeWalkResult::Ok,
+        }
+    })
+    .ok();
+    found
+}
@@ -1,7 +1,14 @@
-use crate::database::entities::{repo_groups, repositories};
+use crate::commands::repo_scan::root_commit;
+use crate::core::groups::descendant_group_ids;
+use crate::core::repo_impact::{DeletionImpact, RepoImpactAnalyzer, RouteAction};
+use crate::database::entities::{repo_groups, repo_status_snapshots, repositories};
 use crate::database::manager::DatabaseManager;
-use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 use tauri::State;
 use uuid::Uuid;
@@ -13,6 +20,8 @@ pub struct RepoNode {
     pub path: String,
     pub group_id: Option<String>,
     pub pinned: bool,
+    pub auto_fetch: bool,
+    pub missing_since: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -52,19 +61,64 @@ pub async fn create_repo_group(
     Ok(())
 }
 
-#[tauri::command]
+/// Preview what deleting a group (and optionally its repositories) would affect
+#[tauri::command(rename_all = "snake_case")]
+pub async fn preview_repo_group_deletion(
+    id: String,
+    delete_repositories: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<DeletionImpact, String> {
+    RepoImpactAnalyzer::analyze_group(&state.connection, &id, delete_repositories.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a group and its subgroups. Repositories inside are moved to the root unless
+/// `delete_repositories` is set. Deleting repositories that routes, tasks or workspaces
+/// depend on requires `confirm`; `route_action` decides what happens to those routes.
+#[tauri::command(rename_all = "snake_case")]
 pub async fn delete_repo_group(
     id: String,
+    delete_repositories: Option<bool>,
+    confirm: Option<bool>,
+    route_action: Option<String>,
+    reassign_to: Option<String>,
     state: State<'_, DatabaseManager>,
 ) -> Result<(), String> {
     let db = &state.connection;
-    // SeaORM Cascade Delete should handle children if configured in DB,
-    // but code-first migration might rely on logical deletion or explicit checks.
-    // For now, simple delete.
-    repo_groups::Entity::delete_by_id(id)
-        .exec(db)
+    let delete_repositories = delete_repositories.unwrap_or(false);
+    let impact = RepoImpactAnalyzer::analyze_group(db, &id, delete_repositories)
         .await
         .map_err(|e| e.to_string())?;
+    if impact.needs_confirmation() && !confirm.unwrap_or(false) {
+        return Err(impact.summary());
+    }
+    let action = RouteAction::parse(route_action.as_deref(), reassign_to)?;
+
+    let repo_ids: Vec<String> = impact.repositories.iter().map(|r| r.id.clone()).collect();
+    let group_ids: Vec<String> = impact.groups.iter().map(|g| g.id.clone()).collect();
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    if delete_repositories {
+        delete_repositories_in(&txn, &repo_ids, &action).await?;
+    } else {
+        repositories::Entity::update_many()
+            .col_expr(
+                repositories::Column::GroupId,
+                sea_orm::sea_query::Expr::value(Option::<String>::None),
+            )
+            .filter(repositories::Column::GroupId.is_in(group_ids.clone()))
+            .exec(&txn)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    repo_groups::Entity::delete_many()
+        .filter(repo_groups::Column::Id.is_in(group_ids))
+        .exec(&txn)
+        .await
+        .map_err(|e| e.to_string())?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
@@ -105,10 +159,10 @@ pub async fn add_repository(
     }
 
     // Check if it's a git repo
-    match git2::Repository::open(repo_path) {
-        Ok(_) => {}
+    let root = match git2::Repository::open(repo_path) {
+        Ok(repo) => root_commit(&repo),
         Err(_) => return Err("Invalid git repository".to_string()),
-    }
+    };
 
     let db = &state.connection;
     let id = Uuid::new_v4().to_string();
@@ -121,6 +175,7 @@ pub async fn add_repository(
         group_id: Set(group_id),
         auth_type: Set("none".to_string()), // Default
         pinned: Set(false),
+        root_commit: Set(root),
         created_at: Set(chrono::Utc::now().naive_utc()),
         ..Default::default()
     };
@@ -145,7 +200,15 @@ pub async fn update_repository(
         .map_err(|e| e.to_string())?;
 
     if let Some(model) = existing {
+        let moved = model.local_path != path;
         let mut active: repositories::ActiveModel = model.into();
+        if moved {
+            // The checkout at the new path may be a different repository
+            active.root_commit = Set(git2::Repository::open(&path)
+                .ok()
+                .and_then(|repo| root_commit(&repo)));
+            active.missing_since = Set(None);
+        }
         active.name = Set(name);
         active.local_path = Set(path);
         active.remote_url = Set(url);
@@ -155,14 +218,75 @@ pub async fn update_repository(
     Ok(())
 }
 
-#[tauri::command]
+/// Preview the routes, task steps and workspace configs that depend on a repository
+#[tauri::command(rename_all = "snake_case")]
+pub async fn preview_repository_deletion(
+    id: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<DeletionImpact, String> {
+    RepoImpactAnalyzer::analyze(&state.connection, &[id])
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a repository. When anything depends on it, `confirm` must be set;
+/// `route_action` ("delete" | "archive" | "reassign" with `reassign_to`) decides what
+/// happens to its routes.
+#[tauri::command(rename_all = "snake_case")]
 pub async fn delete_repository(
     id: String,
+    confirm: Option<bool>,
+    route_action: Option<String>,
+    reassign_to: Option<String>,
     state: State<'_, DatabaseManager>,
 ) -> Result<(), String> {
     let db = &state.connection;
-    repositories::Entity::delete_by_id(id)
-        .exec(db)
+    let repo_ids = vec![id];
+    let impact = RepoImpactAnalyzer::analyze(db, &repo_ids)
+        .await
+        .map_err(|e| e.to_string())?;
+    if impact.needs_confirmation() && !confirm.unwrap_or(false) {
+        return Err(impact.summary());
+    }
+    let action = RouteAction::parse(route_action.as_deref(), reassign_to)?;
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    delete_repositories_in(&txn, &repo_ids, &action).await?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub(crate) async fn delete_repositories_in(
+    txn: &sea_orm::DatabaseTransaction,
+    repo_ids: &[String],
+    action: &RouteAction,
+) -> Result<(), String> {
+    if let RouteAction::Reassign(target) = action {
+        if repo_ids.contains(target) {
+            return Err("Cannot reassign routes to a repository that is being deleted".to_string());
+        }
+        repositories::Entity::find_by_id(target)
+            .one(txn)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Repository to reassign routes to was not found")?;
+        // Git task steps follow the routes so their tasks keep running
+        RepoImpactAnalyzer::reassign_task_steps(txn, repo_ids, target)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    RepoImpactAnalyzer::apply_route_action(txn, repo_ids, action)
+        .await
+        .map_err(|e| e.to_string())?;
+    repo_status_snapshots::Entity::delete_many()
+        .filter(repo_status_snapshots::Column::RepoId.is_in(repo_ids.to_vec()))
+        .exec(txn)
+        .await
+        .map_err(|e| e.to_string())?;
+    repositories::Entity::delete_many()
+        .filter(repositories::Column::Id.is_in(repo_ids.to_vec()))
+        .exec(txn)
         .await
         .map_err(|e| e.to_string())?;
     Ok(())
@@ -235,6 +359,26 @@ pub async fn toggle_pin_repo(
     }
 }
 
+/// Turn periodic background fetching on or off for a repository
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_repo_auto_fetch(
+    id: String,
+    enabled: bool,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    let db = &state.connection;
+    let model = repositories::Entity::find_by_id(&id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Repository not found")?;
+
+    let mut active: repositories::ActiveModel = model.into();
+    active.auto_fetch = Set(enabled);
+    active.update(db).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn list_repo_tree(state: State<'_, DatabaseManager>) -> Result<Vec<GroupNode>, String> {
     let db = &state.connection;
@@ -253,6 +397,54 @@ pub async fn list_repo_tree(state: State<'_, DatabaseManager>) -> Result<Vec<Gro
     Ok(build_tree(&all_groups, &all_repos, None))
 }
 
+/// Ids of `root_id` and all groups nested below it
+pub(crate) fn descendant_repo_group_ids(
+    groups: &[repo_groups::Model],
+    root_id: &str,
+) -> HashSet<String> {
+    descendant_group_ids(
+        groups
+            .iter()
+            .map(|g| (g.id.as_str(), g.parent_id.as_deref())),
+        root_id,
+    )
+}
+
+/// Repositories in a group, and in its nested groups with `recursive`, ordered by name
+pub(crate) async fn group_repositories(
+    db: &DatabaseConnection,
+    group_id: &str,
+    recursive: bool,
+) -> Result<Vec<repositories::Model>, String> {
+    let group_ids: HashSet<String> = if recursive {
+        let groups = repo_groups::Entity::find()
+            .all(db)
+            .await
+            .map_err(|e| e.to_string())?;
+        descendant_repo_group_ids(&groups, group_id)
+    } else {
+        HashSet::from([group_id.to_string()])
+    };
+    repositories::Entity::find()
+        .filter(repositories::Column::GroupId.is_in(group_ids))
+        .order_by_asc(repositories::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The repository row for `id`, or "Repository not found"
+pub(crate) async fn find_repository(
+    db: &DatabaseConnection,
+    id: &str,
+) -> Result<repositories::Model, String> {
+    repositories::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Repository not found".to_string())
+}
+
 fn build_tree(
     groups: &[repo_groups::Model],
     repos: &[repositories::Model],
@@ -272,6 +464,8 @@ fn build_tree(
                     path: r.local_path.clone(),
                     group_id: r.group_id.clone(),
                     pinned: r.pinned,
+                    auto_fetch: r.auto_fetch,
+                    missing_since: r.missing_since,
                 })
                 .collect();
 
@@ -294,6 +488,8 @@ fn build_tree(
                 path: r.local_path.clone(),
                 group_id: None,
                 pinned: r.pinned,
+                auto_fetch: r.auto_fetch,
+                missing_since: r.missing_since,
             })
             .collect();
 
@@ -0,0 +1,49 @@
+use crate::commands::repo::group_repositories;
+use crate::core::repo_health::{RepoHealth, RepoHealthChecker};
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use sea_orm::EntityTrait;
+use tauri::State;
+
+/// Check a repository for problems with its checkout: missing path, remote or branch
+/// drift, no upstream, large untracked files, merged branches and unreadable objects.
+/// `deep` reads every object instead of only those HEAD needs.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn check_repo_health(
+    id: String,
+    deep: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<RepoHealth, String> {
+    let repo = repositories::Entity::find_by_id(id)
+        .one(&state.connection)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Repository not found")?;
+    let checker = RepoHealthChecker {
+        deep: deep.unwrap_or(false),
+    };
+    tokio::task::spawn_blocking(move || checker.check(&repo))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Check every repository in a group, worst first
+#[tauri::command(rename_all = "snake_case")]
+pub async fn check_group_health(
+    group_id: String,
+    recursive: Option<bool>,
+    deep: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<RepoHealth>, String> {
+    let repos = group_repositories(&state.connection, &group_id, recursive.unwrap_or(true)).await?;
+    let checker = RepoHealthChecker {
+        deep: deep.unwrap_or(false),
+    };
+    let mut reports = tokio::task::spawn_blocking(move || {
+        repos.iter().map(|r| checker.check(r)).collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    reports.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.name.cmp(&b.name)));
+    Ok(reports)
+}
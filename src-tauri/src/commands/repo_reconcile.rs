@@ -0,0 +1,115 @@
+use crate::commands::repo::delete_repositories_in;
+use crate::commands::repo_scan::{
+    discover_repositories, ensure_repo_group_path, group_segments, insert_scanned_repo,
+    GroupingMode,
+};
+use crate::core::reconcile::{
+    diff, prepare, PreparedAction, ReconcileAction, ReconcileReport, ReconcileSummary,
+};
+use crate::core::relocate::remember_root;
+use crate::core::repo_impact::RouteAction;
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TransactionTrait};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Rescan `root_path` and compare it with the repositories registered under it:
+/// new checkouts, checkouts that are gone, changed remotes, and remotes checked out twice
+#[tauri::command(rename_all = "snake_case")]
+pub async fn reconcile_scan(
+    root_path: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<ReconcileReport, String> {
+    let root = PathBuf::from(root_path.trim());
+    if !root.is_dir() {
+        return Err(format!("Directory does not exist: {}", root.display()));
+    }
+    let repos = repositories::Entity::find()
+        .all(&state.connection)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tokio::task::spawn_blocking(move || {
+        let scanned = discover_repositories(&root);
+        diff(&root, scanned, &repos)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Apply the chosen reconcile actions in one transaction; nothing changes if one fails.
+/// New checkouts are grouped by `grouping_mode` like a scan import.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn apply_reconcile(
+    root_path: String,
+    actions: Vec<ReconcileAction>,
+    grouping_mode: GroupingMode,
+    state: State<'_, DatabaseManager>,
+) -> Result<ReconcileSummary, String> {
+    let db = &state.connection;
+    let root_path = root_path.trim().to_string();
+    let all = repositories::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let registered: HashSet<String> = all.iter().map(|r| r.local_path.clone()).collect();
+    let repos: HashMap<String, repositories::Model> =
+        all.into_iter().map(|r| (r.id.clone(), r)).collect();
+
+    let root = PathBuf::from(&root_path);
+    let prepared =
+        tokio::task::spawn_blocking(move || prepare(&root, actions, &repos, &registered))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    let mut summary = ReconcileSummary::default();
+    let mut group_cache: HashMap<String, String> = HashMap::new();
+    let now = chrono::Utc::now().naive_utc();
+
+    for action in prepared {
+        match action {
+            PreparedAction::Add { repo, root_commit } => {
+                let segments = group_segments(&repo, &grouping_mode);
+                let group_id = ensure_repo_group_path(&txn, &segments, &mut group_cache).await?;
+                insert_scanned_repo(&txn, repo, group_id, root_commit).await?;
+                summary.added += 1;
+            }
+            PreparedAction::MarkMissing { repo } => {
+                // Keep the first time it was found gone
+                if repo.missing_since.is_none() {
+                    let mut active: repositories::ActiveModel = repo.into();
+                    active.missing_since = Set(Some(now));
+                    active.update(&txn).await.map_err(|e| e.to_string())?;
+                }
+                summary.marked_missing += 1;
+            }
+            PreparedAction::Update { repo, remote_url } => {
+                let mut active: repositories::ActiveModel = repo.into();
+                active.remote_url = Set(remote_url);
+                active.missing_since = Set(None);
+                active.update(&txn).await.map_err(|e| e.to_string())?;
+                summary.updated += 1;
+            }
+            PreparedAction::Merge { keep, merge_ids } => {
+                delete_repositories_in(&txn, &merge_ids, &RouteAction::Reassign(keep.id.clone()))
+                    .await?;
+                if keep.missing_since.is_some() {
+                    let mut active: repositories::ActiveModel = keep.into();
+                    active.missing_since = Set(None);
+                    active.update(&txn).await.map_err(|e| e.to_string())?;
+                }
+                summary.merged += merge_ids.len();
+            }
+        }
+    }
+
+    remember_root(&txn, &root_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+    Ok(summary)
+}
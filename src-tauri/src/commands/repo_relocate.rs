@@ -0,0 +1,98 @@
+use crate::commands::repo_clone::workspace_root;
+use crate::core::relocate::{
+    apply_moves, repository_roots, verify_moves, RelocationMove, RelocationProposal, Relocator,
+};
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use sea_orm::{EntityTrait, TransactionTrait};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Search for checkouts that repositories were moved to. Without `repo_ids` every repository
+/// whose path is missing is looked up; without `roots` the configured roots and the workspace
+/// root are searched.
+/// `match_root_commit` also requires the same root commit as the stored one.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn find_relocation_candidates(
+    repo_ids: Option<Vec<String>>,
+    roots: Option<Vec<String>>,
+    match_root_commit: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<RelocationProposal>, String> {
+    let db = &state.connection;
+    let roots = match roots.filter(|r| !r.is_empty()) {
+        Some(roots) => roots,
+        None => {
+            let mut roots = repository_roots(db).await.map_err(|e| e.to_string())?;
+            // Clones land in the workspace root
+            if let Ok(workspace) = workspace_root(&state).await {
+                let workspace = workspace.to_string_lossy().to_string();
+                if !roots.contains(&workspace) {
+                    roots.push(workspace);
+                }
+            }
+            roots
+        }
+    };
+    if roots.is_empty() {
+        return Err("No repository roots configured".to_string());
+    }
+
+    let all = repositories::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let registered: HashSet<String> = all.iter().map(|r| r.local_path.clone()).collect();
+    let repos: Vec<repositories::Model> = match repo_ids {
+        Some(ids) => all.into_iter().filter(|r| ids.contains(&r.id)).collect(),
+        None => all
+            .into_iter()
+            .filter(|r| !Path::new(&r.local_path).exists())
+            .collect(),
+    };
+    if repos.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let relocator = Relocator {
+        roots: roots.into_iter().map(PathBuf::from).collect(),
+        match_root_commit: match_root_commit.unwrap_or(false),
+    };
+    tokio::task::spawn_blocking(move || relocator.propose(&repos, &registered))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Point repositories at their new checkouts. Every move is checked first (a git checkout,
+/// not registered already, same remote and root commit) and nothing changes if one fails.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn relocate_repositories(
+    moves: Vec<RelocationMove>,
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<repositories::Model>, String> {
+    let db = &state.connection;
+    if moves.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let all = repositories::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let registered: HashSet<String> = all.iter().map(|r| r.local_path.clone()).collect();
+    let repos: HashMap<String, repositories::Model> =
+        all.into_iter().map(|r| (r.id.clone(), r)).collect();
+
+    let verified = tokio::task::spawn_blocking(move || verify_moves(&moves, &repos, &registered))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    let updated = apply_moves(&txn, verified)
+        .await
+        .map_err(|e| e.to_string())?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+    Ok(updated)
+}
@@ -1,9 +1,14 @@
+use crate::core::relocate::remember_root;
 use crate::database::entities::{repo_groups, repositories};
 use crate::database::manager::DatabaseManager;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use once_cell::sync::Lazy;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set, TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Mutex;
 use tauri::{Emitter, State};
 use uuid::Uuid;
 use walkdir::WalkDir;
@@ -40,87 +45,127 @@ pub async fn scan_local_repos(root_path: String) -> Result<Vec<ScannedRepo>, Str
         return Err("无效的根目录".to_string());
     }
 
-    let root_path_str = root.to_string_lossy().to_string();
+    let root = root.to_path_buf();
 
     // 使用 spawn_blocking 在单独的线程中执行耗时的扫描操作
-    let scanned_repos = tokio::task::spawn_blocking(move || {
-        let mut results = Vec::new();
-        let walker = WalkDir::new(&root_path_str).follow_links(true).into_iter();
-
-        for entry in walker.filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            // 跳过常见的非源码目录以提高扫描速度
-            if name == "node_modules"
-                || name == "target"
-                || name == ".trash"
-                || name == "dist"
-                || name == "build"
-            {
-                return false;
-            }
-            true
-        }) {
-            if let Ok(entry) = entry {
-                if entry.file_type().is_dir() && entry.file_name() == ".git" {
-                    // 发现 .git 目录，其父目录即为仓库根目录
-                    if let Some(repo_path) = entry.path().parent() {
-                        let absolute_path = repo_path.to_string_lossy().to_string();
-                        let repo_name = repo_path
-                            .file_name()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .to_string();
-
-                        // 尝试获取 git 远程地址
-                        let remote_url = if let Ok(repo) = git2::Repository::open(repo_path) {
-                            repo.find_remote("origin")
-                                .ok()
-                                .and_then(|remote| remote.url().map(|u| u.to_string()))
-                                .or_else(|| {
-                                    // 如果没有 origin，尝试获取第一个远程仓库
-                                    repo.remotes().ok().and_then(|remotes| {
-                                        remotes.get(0).and_then(|name| {
-                                            repo.find_remote(name).ok().and_then(|remote| {
-                                                remote.url().map(|u| u.to_string())
-                                            })
-                                        })
-                                    })
-                                })
-                        } else {
-                            None
-                        };
-
-                        // 计算相对路径
-                        // 如果 strip_prefix 失败，则使用仓库名称作为回退
-                        let relative_path = match repo_path.strip_prefix(&root_path_str) {
-                            Ok(p) => p.to_string_lossy().to_string(),
-                            Err(_) => repo_name.clone(),
-                        };
-
-                        results.push(ScannedRepo {
-                            path: absolute_path,
-                            name: repo_name,
-                            relative_path,
-                            remote_url,
-                        });
-                    }
+    tokio::task::spawn_blocking(move || discover_repositories(&root))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 功能：递归查找根目录下的 git 仓库（扫描导入、仓库迁移与重新扫描对账共用）
+// 参数：root - 根目录
+// 返回：找到的仓库，相对路径相对于 root
+pub(crate) fn discover_repositories(root: &Path) -> Vec<ScannedRepo> {
+    let root_path_str = root.to_string_lossy().to_string();
+    let mut results = Vec::new();
+    let walker = WalkDir::new(&root_path_str).follow_links(true).into_iter();
+
+    for entry in walker.filter_entry(|e| {
+        let name = e.file_name().to_string_lossy();
+        // 跳过常见的非源码目录以提高扫描速度
+        if name == "node_modules"
+            || name == "target"
+            || name == ".trash"
+            || name == "dist"
+            || name == "build"
+        {
+            return false;
+        }
+        true
+    }) {
+        if let Ok(entry) = entry {
+            if entry.file_type().is_dir() && entry.file_name() == ".git" {
+                // 发现 .git 目录，其父目录即为仓库根目录
+                if let Some(repo_path) = entry.path().parent() {
+                    results.push(scanned_repo(root, repo_path));
                 }
             }
         }
-        results
-    })
-    .await
-    .map_err(|e| e.to_string())?;
+    }
+    results
+}
+
+// 功能：读取 root 下某个仓库的名称、相对路径与远程地址
+pub(crate) fn scanned_repo(root: &Path, repo_path: &Path) -> ScannedRepo {
+    let repo_name = repo_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    // 尝试获取 git 远程地址
+    let remote_url = git2::Repository::open(repo_path)
+        .ok()
+        .and_then(|repo| primary_remote_url(&repo));
+
+    // 计算相对路径
+    // 如果 strip_prefix 失败，则使用仓库名称作为回退
+    let relative_path = match repo_path.strip_prefix(root) {
+        Ok(p) => p.to_string_lossy().to_string(),
+        Err(_) => repo_name.clone(),
+    };
+
+    ScannedRepo {
+        path: repo_path.to_string_lossy().to_string(),
+        name: repo_name,
+        relative_path,
+        remote_url,
+    }
+}
+
+// 功能：获取仓库的远程地址，优先 origin，否则取第一个远程仓库
+pub(crate) fn primary_remote_url(repo: &git2::Repository) -> Option<String> {
+    repo.find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().map(|u| u.to_string()))
+        .or_else(|| {
+            // 如果没有 origin，尝试获取第一个远程仓库
+            repo.remotes().ok().and_then(|remotes| {
+                remotes.get(0).and_then(|name| {
+                    repo.find_remote(name)
+                        .ok()
+                        .and_then(|remote| remote.url().map(|u| u.to_string()))
+                })
+            })
+        })
+}
 
-    Ok(scanned_repos)
+// 按 HEAD 缓存根提交，避免每次扫描都回溯整段历史
+static ROOT_COMMITS: Lazy<Mutex<HashMap<git2::Oid, String>>> = Lazy::new(Default::default);
+const ROOT_COMMIT_CACHE_LIMIT: usize = 4096;
+
+// 功能：获取 HEAD 沿第一父提交回溯到的根提交；之后合并进来的其他历史不影响结果
+// 说明：与远程地址一起用于识别被移动的仓库
+pub(crate) fn root_commit(repo: &git2::Repository) -> Option<String> {
+    // 浅克隆的边界提交并不是真正的根提交
+    if repo.is_shallow() {
+        return None;
+    }
+    let head = repo.head().ok()?.target()?;
+    if let Some(root) = ROOT_COMMITS.lock().unwrap().get(&head) {
+        return Some(root.clone());
+    }
+
+    // 只沿第一父提交回溯，不遍历合并进来的分支
+    let mut walk = repo.revwalk().ok()?;
+    walk.push(head).ok()?;
+    walk.simplify_first_parent().ok()?;
+    let root = walk.flatten().last()?.to_string();
+    let mut cache = ROOT_COMMITS.lock().unwrap();
+    if cache.len() >= ROOT_COMMIT_CACHE_LIMIT {
+        cache.clear();
+    }
+    cache.insert(head, root.clone());
+    Some(root)
 }
 
 // 功能：将扫描到的仓库导入数据库，自动创建分组
-// 参数：_root_path - 根路径（暂未使用），repos - 待导入的仓库列表
+// 参数：root_path - 扫描的根路径，记入仓库根目录设置以便之后查找被移动的仓库；repos - 待导入的仓库列表
 // 返回：成功消息
 #[tauri::command(rename_all = "snake_case")]
 pub async fn import_scanned_repos(
-    _root_path: String,
+    root_path: String,
     repos: Vec<ScannedRepo>,
     grouping_mode: GroupingMode,
     window: tauri::Window,
@@ -129,6 +174,23 @@ pub async fn import_scanned_repos(
     let db = &state.connection;
     let total = repos.len();
 
+    // 根提交需要遍历历史，在事务之外计算
+    let paths: Vec<String> = repos.iter().map(|r| r.path.clone()).collect();
+    let mut root_commits: HashMap<String, Option<String>> =
+        tokio::task::spawn_blocking(move || {
+            paths
+                .into_iter()
+                .map(|path| {
+                    let root = git2::Repository::open(&path)
+                        .ok()
+                        .and_then(|repo| root_commit(&repo));
+                    (path, root)
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
     // 开启事务
     let txn = db.begin().await.map_err(|e| e.to_string())?;
 
@@ -150,94 +212,9 @@ pub async fn import_scanned_repos(
         );
 
         // 1. 创建或查找分组
-        let segments = match grouping_mode {
-            GroupingMode::Path => {
-                let repo_path = Path::new(&repo.relative_path);
-                repo_path
-                    .parent()
-                    .map(|p| {
-                        p.components()
-                            .filter_map(|c| {
-                                let s = c.as_os_str().to_string_lossy().to_string();
-                                if s.is_empty() || s == "." {
-                                    None
-                                } else {
-                                    Some(s)
-                                }
-                            })
-                            .collect::<Vec<String>>()
-                    })
-                    .unwrap_or_default()
-            }
-            GroupingMode::GitUrl => {
-                if let Some(url) = &repo.remote_url {
-                    parse_git_url_segments(url)
-                } else {
-                    // 没有 URL 时回退到路径
-                    let repo_path = Path::new(&repo.relative_path);
-                    repo_path
-                        .parent()
-                        .map(|p| {
-                            p.components()
-                                .filter_map(|c| {
-                                    let s = c.as_os_str().to_string_lossy().to_string();
-                                    if s.is_empty() || s == "." {
-                                        None
-                                    } else {
-                                        Some(s)
-                                    }
-                                })
-                                .collect::<Vec<String>>()
-                        })
-                        .unwrap_or_default()
-                }
-            }
-        };
-
-        let mut current_parent_id: Option<String> = None;
-        let mut current_path_stack = String::new();
-
-        for segment in segments {
-            if !current_path_stack.is_empty() {
-                current_path_stack.push('/');
-            }
-            current_path_stack.push_str(&segment);
+        let segments = group_segments(&repo, &grouping_mode);
 
-            let path_key = current_path_stack.clone();
-
-            if let Some(id) = group_cache.get(&path_key) {
-                current_parent_id = Some(id.clone());
-            } else {
-                // 查询数据库
-                let existing_group = repo_groups::Entity::find()
-                    .filter(repo_groups::Column::Name.eq(&segment))
-                    .filter(match &current_parent_id {
-                        Some(pid) => repo_groups::Column::ParentId.eq(pid),
-                        None => repo_groups::Column::ParentId.is_null(),
-                    })
-                    .one(&txn)
-                    .await
-                    .map_err(|e| e.to_string())?;
-
-                if let Some(group) = existing_group {
-                    current_parent_id = Some(group.id.clone());
-                    group_cache.insert(path_key, group.id);
-                } else {
-                    // 创建新分组
-                    let new_id = Uuid::new_v4().to_string();
-                    let new_group = repo_groups::ActiveModel {
-                        id: Set(new_id.clone()),
-                        name: Set(segment),
-                        parent_id: Set(current_parent_id.clone()),
-                        sort_order: Set(0),
-                    };
-                    new_group.insert(&txn).await.map_err(|e| e.to_string())?;
-
-                    current_parent_id = Some(new_id.clone());
-                    group_cache.insert(path_key, new_id);
-                }
-            }
-        }
+        let current_parent_id = ensure_repo_group_path(&txn, &segments, &mut group_cache).await?;
 
         // 2. 创建或更新仓库
         // 检查是否已存在
@@ -249,61 +226,213 @@ pub async fn import_scanned_repos(
 
         if existing_repo.is_none() {
             // 创建新仓库
-            let new_repo_id = Uuid::new_v4().to_string();
-            let new_repo = repositories::ActiveModel {
-                id: Set(new_repo_id),
-                group_id: Set(current_parent_id), // 关联到最后一个找到或创建的分组
-                name: Set(repo.name),
-                local_path: Set(repo.path),
-                remote_url: Set(repo.remote_url),
-                auth_type: Set("none".to_string()),
-                created_at: Set(chrono::Utc::now().naive_utc()),
-                ..Default::default()
-            };
-            new_repo.insert(&txn).await.map_err(|e| e.to_string())?;
+            let root = root_commits.remove(&repo.path).flatten();
+            // 关联到最后一个找到或创建的分组
+            insert_scanned_repo(&txn, repo, current_parent_id, root).await?;
             created_count += 1;
         }
     }
 
+    remember_root(&txn, &root_path)
+        .await
+        .map_err(|e| e.to_string())?;
     txn.commit().await.map_err(|e| e.to_string())?;
 
     Ok(format!("成功导入 {} 个仓库", created_count))
 }
 
-fn parse_git_url_segments(url: &str) -> Vec<String> {
-    // 移除 .git 后缀
-    let clean_url = url.trim_end_matches(".git");
+// 功能：按分组方式计算仓库所属分组的名称链
+pub(crate) fn group_segments(repo: &ScannedRepo, grouping_mode: &GroupingMode) -> Vec<String> {
+    match (grouping_mode, &repo.remote_url) {
+        (GroupingMode::GitUrl, Some(url)) => parse_git_url_segments(url),
+        // 按路径分组；没有 URL 时也回退到路径
+        _ => Path::new(&repo.relative_path)
+            .parent()
+            .map(|p| {
+                p.components()
+                    .filter_map(|c| {
+                        let s = c.as_os_str().to_string_lossy().to_string();
+                        if s.is_empty() || s == "." {
+                            None
+                        } else {
+                            Some(s)
+                        }
+                    })
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+// 功能：将一个扫描到的仓库写入数据库
+pub(crate) async fn insert_scanned_repo<C: ConnectionTrait>(
+    txn: &C,
+    repo: ScannedRepo,
+    group_id: Option<String>,
+    root_commit: Option<String>,
+) -> Result<repositories::Model, String> {
+    let new_repo = repositories::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        group_id: Set(group_id),
+        name: Set(repo.name),
+        local_path: Set(repo.path),
+        remote_url: Set(repo.remote_url),
+        auth_type: Set("none".to_string()),
+        root_commit: Set(root_commit),
+        created_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+    new_repo.insert(txn).await.map_err(|e| e.to_string())
+}
 
-    // 识别是 SSH 格式还是 HTTP 格式
-    if clean_url.contains('@') && clean_url.contains(':') {
-        // git@github.com:owner/repo
-        let parts: Vec<&str> = clean_url.split(':').collect();
-        if parts.len() < 2 {
-            return vec![];
+// 功能：按片段逐级查找或创建仓库分组
+// 参数：txn - 数据库连接或事务，segments - 分组名称链，group_cache - 路径字符串到分组ID的缓存
+// 返回：最深一级分组 ID；segments 为空时返回 None（根目录）
+pub(crate) async fn ensure_repo_group_path<C: ConnectionTrait>(
+    txn: &C,
+    segments: &[String],
+    group_cache: &mut HashMap<String, String>,
+) -> Result<Option<String>, String> {
+    let mut current_parent_id: Option<String> = None;
+    let mut current_path_stack = String::new();
+
+    for segment in segments {
+        if !current_path_stack.is_empty() {
+            current_path_stack.push('/');
+        }
+        current_path_stack.push_str(segment);
+
+        let path_key = current_path_stack.clone();
+
+        if let Some(id) = group_cache.get(&path_key) {
+            current_parent_id = Some(id.clone());
+        } else {
+            // 查询数据库
+            let existing_group = repo_groups::Entity::find()
+                .filter(repo_groups::Column::Name.eq(segment))
+                .filter(match &current_parent_id {
+                    Some(pid) => repo_groups::Column::ParentId.eq(pid),
+                    None => repo_groups::Column::ParentId.is_null(),
+                })
+                .one(txn)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if let Some(group) = existing_group {
+                current_parent_id = Some(group.id.clone());
+                group_cache.insert(path_key, group.id);
+            } else {
+                // 创建新分组
+                let new_id = Uuid::new_v4().to_string();
+                let new_group = repo_groups::ActiveModel {
+                    id: Set(new_id.clone()),
+                    name: Set(segment.clone()),
+                    parent_id: Set(current_parent_id.clone()),
+                    sort_order: Set(0),
+                };
+                new_group.insert(txn).await.map_err(|e| e.to_string())?;
+
+                current_parent_id = Some(new_id.clone());
+                group_cache.insert(path_key, new_id);
+            }
         }
+    }
 
-        let host_part = parts[0]; // git@github.com
-        let path_part = parts[1]; // owner/repo
+    Ok(current_parent_id)
+}
 
-        let host = host_part.split('@').nth(1).unwrap_or(host_part);
-        let mut segments = vec![host.to_string()];
-        segments.extend(path_part.split('/').map(|s| s.to_string()));
+pub(crate) fn parse_git_url_segments(url: &str) -> Vec<String> {
+    let (host, path) = split_remote_url(url);
+    if host.is_empty() {
+        return vec![];
+    }
 
-        // 移除最后一个片段（仓库名本身）
-        if segments.len() > 0 {
-            segments.pop();
+    let mut segments = vec![host.to_string()];
+    segments.extend(
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string()),
+    );
+    // 移除最后一个片段（仓库名本身）
+    segments.pop();
+    segments
+}
+
+// 功能：拆分远程地址为主机与仓库路径，保留大小写
+// 说明：支持 https://、ssh://user@host:22/ 与 git@host:owner/repo 形式，
+//       去掉协议、用户名、端口、末尾的 '/' 以及 .git 后缀
+fn split_remote_url(url: &str) -> (&str, &str) {
+    let trimmed = url.trim().trim_end_matches('/');
+    let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+
+    let without_scheme = match trimmed.split_once("://") {
+        Some((_, rest)) => rest,
+        None => trimmed,
+    };
+    let without_user = match without_scheme.split_once('@') {
+        Some((_, rest)) => rest,
+        None => without_scheme,
+    };
+
+    // 主机与路径之间可能是 '/'（URL 格式）或 ':'（SCP 格式）
+    match without_user.find([':', '/']) {
+        Some(pos) => {
+            let host = &without_user[..pos];
+            let mut path = &without_user[pos + 1..];
+            // ssh://host:22/owner/repo 中的端口号不属于路径
+            if without_user[pos..].starts_with(':') {
+                if let Some((port, rest)) = path.split_once('/') {
+                    if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) {
+                        path = rest;
+                    }
+                }
+            }
+            (host, path.trim_matches('/'))
         }
-        segments
+        None => (without_user, ""),
+    }
+}
+
+// 功能：规范化远程地址，用于跨机器比较同一仓库
+// 说明：忽略协议、用户名、端口、.git 后缀以及大小写差异，
+//       例如 git@github.com:Owner/Repo.git 与 https://github.com/owner/repo 视为相同
+pub(crate) fn normalize_remote_url(url: &str) -> String {
+    let (host, path) = split_remote_url(url);
+    if path.is_empty() {
+        host.to_lowercase()
     } else {
-        // https://github.com/owner/repo
-        let parts: Vec<&str> = clean_url.split("://").collect();
-        let path = if parts.len() > 1 { parts[1] } else { parts[0] };
-
-        let mut segments: Vec<String> = path.split('/').map(|s| s.to_string()).collect();
-        // 移除最后一个片段（仓库名本身）
-        if segments.len() > 0 {
-            segments.pop();
+        format!("{}/{}", host, path).to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_remote_url_ignores_protocol_user_port_and_case() {
+        let expected = "github.com/owner/repo";
+        for url in [
+            "git@github.com:Owner/Repo.git",
+            "https://github.com/owner/repo",
+            "https://user@github.com/Owner/Repo.git/",
+            "ssh://git@github.com:22/owner/repo.git",
+            "ssh://git@GitHub.com/owner/repo",
+        ] {
+            assert_eq!(normalize_remote_url(url), expected, "{}", url);
         }
-        segments
+    }
+
+    #[test]
+    fn normalize_remote_url_keeps_nested_paths_apart() {
+        assert_eq!(
+            normalize_remote_url("https://gitlab.com/group/sub/repo.git"),
+            "gitlab.com/group/sub/repo"
+        );
+        assert_ne!(
+            normalize_remote_url("git@gitlab.com:group/sub/repo.git"),
+            normalize_remote_url("git@gitlab.com:group/repo.git")
+        );
+        assert_eq!(normalize_remote_url("  Example.com  "), "example.com");
     }
 }
@@ -1,8 +1,9 @@
-use crate::commands::git_ops::get_git_log;
+use crate::core::git_history::{GitHistory, HistoryQuery};
 use crate::database::entities::repositories;
 use crate::database::entities::task_execution_logs;
 use crate::database::manager::DatabaseManager;
 use chrono::{Duration, Utc};
+use git2::Repository;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde::Serialize;
 use tauri::State;
@@ -57,23 +58,36 @@ pub async fn generate_weekly_report(state: State<'_, DatabaseManager>) -> Result
         .map_err(|e| e.to_string())?;
 
     let mut recent_commits = Vec::new();
+    let query = HistoryQuery {
+        since: Some(one_week_ago.to_rfc3339()),
+        limit: Some(20),
+        include_stats: Some(false),
+        ..Default::default()
+    };
 
     for repo in repos {
-        // Just get last 5 commits for report brevity
-        if let Ok(commits) = get_git_log(repo.local_path, 5) {
-            for c in commits {
-                // Parse time to check if within week?
-                // get_git_log returns formatted time string.
-                // For simplicity, we just include them as "Recent Activity"
+        let Ok(git_repo) = Repository::open(&repo.local_path) else {
+            continue;
+        };
+        if let Ok(page) = GitHistory::query(&git_repo, &query) {
+            for c in page.commits {
                 recent_commits.push(CommitSummary {
                     repo_name: repo.name.clone(),
-                    message: c.message,
-                    author: c.author,
-                    time: c.time,
+                    message: c.summary,
+                    author: c.author_name,
+                    time: c.authored_at,
                 });
             }
         }
     }
+    // Newest first across all repositories
+    recent_commits.sort_by_key(|c| {
+        std::cmp::Reverse(
+            chrono::DateTime::parse_from_rfc3339(&c.time)
+                .map(|t| t.timestamp())
+                .unwrap_or(0),
+        )
+    });
 
     // Format as Markdown
     let mut md = String::new();
@@ -84,7 +98,7 @@ pub async fn generate_weekly_report(state: State<'_, DatabaseManager>) -> Result
     md.push_str(&format!("- **Success**: ✅ {}\n", success_count));
     md.push_str(&format!("- **Failed**: ❌ {}\n\n", fail_count));
 
-    md.push_str("## 💻 Recent Commits (Last 7 Days Context)\n");
+    md.push_str("## 💻 Recent Commits (Last 7 Days)\n");
     if recent_commits.is_empty() {
         md.push_str("_No commit activity detected._\n");
     } else {
@@ -1,8 +1,13 @@
+use crate::core::diff::DiffEngine;
+use crate::core::groups::descendant_group_ids;
+use crate::core::mapping_history::MappingHistory;
+use crate::core::repo_routes::{find_route, RepoDefinedRoute, RepoRouteRegistry};
 use crate::database::entities::{route_groups, routes};
 use crate::database::manager::DatabaseManager;
 use glob::Pattern;
-use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
+use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set, TransactionTrait};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tauri::State;
 use uuid::Uuid;
 
@@ -13,6 +18,12 @@ pub struct RouteNode {
     pub group_id: Option<String>,
     pub source_id: Option<String>,
     pub target_id: Option<String>,
+    /// Declared in a repository's `.sourcebridge.yml` rather than stored in the database
+    #[serde(default)]
+    pub read_only: bool,
+    /// Kept after one of its repositories was deleted; needs new repositories before it can sync
+    #[serde(default)]
+    pub archived: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -23,7 +34,7 @@ pub struct RouteGroupNode {
     pub routes: Vec<RouteNode>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct MappingRule {
     pub source: String,
     pub target: String,
@@ -129,6 +140,7 @@ pub async fn update_route(
     group_id: Option<String>,
     state: State<'_, DatabaseManager>,
 ) -> Result<(), String> {
+    RepoRouteRegistry::ensure_mutable(&id)?;
     let db = &state.connection;
     let existing = routes::Entity::find_by_id(&id)
         .one(db)
@@ -139,6 +151,11 @@ pub async fn update_route(
         let mut active: routes::ActiveModel = model.into();
         active.name = Set(name);
         active.description = Set(description);
+        // Pointing an archived route at repositories again brings it back
+        if source_id.is_some() && target_id.is_some() {
+            active.archived_at = Set(None);
+            active.archived_reason = Set(None);
+        }
         active.main_repo_id = Set(source_id);
         active.slave_repo_id = Set(target_id);
         active.group_id = Set(group_id);
@@ -154,6 +171,7 @@ pub async fn update_route_mappings(
     mappings: String,
     state: State<'_, DatabaseManager>,
 ) -> Result<(), String> {
+    RepoRouteRegistry::ensure_mutable(&id)?;
     let db = &state.connection;
     let existing = routes::Entity::find_by_id(&id)
         .one(db)
@@ -161,16 +179,65 @@ pub async fn update_route_mappings(
         .map_err(|e| e.to_string())?;
 
     if let Some(model) = existing {
+        // Route update and revision are written together so history never misses a change
+        let txn = db.begin().await.map_err(|e| e.to_string())?;
+        let previous = model.mappings.clone();
+
         let mut active: routes::ActiveModel = model.into();
-        active.mappings = Set(Some(mappings));
+        active.mappings = Set(Some(mappings.clone()));
         active.updated_at = Set(chrono::Utc::now().naive_utc());
-        active.update(db).await.map_err(|e| e.to_string())?;
+        active.update(&txn).await.map_err(|e| e.to_string())?;
+
+        MappingHistory::record(&txn, &id, previous, Some(mappings), None)
+            .await
+            .map_err(|e| e.to_string())?;
+        txn.commit().await.map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
+/// Copy a route (including repository-defined ones) into a new database route.
+/// `source_id` / `target_id` retarget the copy; omitted values keep the original repositories.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn clone_route(
+    id: String,
+    name: Option<String>,
+    source_id: Option<String>,
+    target_id: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<String, String> {
+    let db = &state.connection;
+    let original = find_route(db, &id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Route not found")?;
+
+    let new_id = Uuid::new_v4().to_string();
+    let note = format!("Cloned from '{}'", original.name);
+    let active = routes::ActiveModel {
+        id: Set(new_id.clone()),
+        name: Set(name.unwrap_or_else(|| format!("{} (Copy)", original.name))),
+        description: Set(original.description),
+        main_repo_id: Set(source_id.or(original.main_repo_id)),
+        slave_repo_id: Set(target_id.or(original.slave_repo_id)),
+        group_id: Set(original.group_id),
+        mappings: Set(original.mappings.clone()),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    active.insert(&txn).await.map_err(|e| e.to_string())?;
+    MappingHistory::record(&txn, &new_id, None, original.mappings, Some(note))
+        .await
+        .map_err(|e| e.to_string())?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+    Ok(new_id)
+}
+
 #[tauri::command]
 pub async fn delete_route(id: String, state: State<'_, DatabaseManager>) -> Result<(), String> {
+    RepoRouteRegistry::ensure_mutable(&id)?;
     let db = &state.connection;
     routes::Entity::delete_by_id(id)
         .exec(db)
@@ -185,6 +252,7 @@ pub async fn update_route_group_id(
     group_id: Option<String>,
     state: State<'_, DatabaseManager>,
 ) -> Result<(), String> {
+    RepoRouteRegistry::ensure_mutable(&id)?;
     let db = &state.connection;
     let existing = routes::Entity::find_by_id(&id)
         .one(db)
@@ -241,7 +309,17 @@ pub async fn list_route_tree(
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(build_tree(&all_groups, &all_routes, None))
+    let mut tree = build_tree(&all_groups, &all_routes, None);
+
+    // Routes declared in repositories are appended as a read-only virtual group
+    let repo_defined = RepoRouteRegistry::load_all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !repo_defined.is_empty() {
+        tree.push(build_repo_defined_group(&repo_defined));
+    }
+
+    Ok(tree)
 }
 
 #[tauri::command]
@@ -250,30 +328,53 @@ pub async fn get_route_details(
     state: State<'_, DatabaseManager>,
 ) -> Result<Option<routes::Model>, String> {
     let db = &state.connection;
-    let route = routes::Entity::find_by_id(id)
-        .one(db)
-        .await
-        .map_err(|e| e.to_string())?;
+    let route = find_route(db, &id).await.map_err(|e| e.to_string())?;
     Ok(route)
 }
 
+use crate::commands::git_changes::{commit_signature, configured_identity};
 use crate::core::sync::SyncEngine;
 
+/// Sync a route. With `commit` the synced files are committed in the target with
+/// provenance trailers; returns that commit, if one was made.
 #[tauri::command]
-pub async fn sync_route(id: String, state: State<'_, DatabaseManager>) -> Result<(), String> {
+pub async fn sync_route(
+    id: String,
+    commit: Option<bool>,
+    message: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<Option<String>, String> {
     println!("[sync_route] Starting sync for route {}", id);
 
-    match SyncEngine::execute_sync(&id, &state).await {
+    let result = match SyncEngine::execute_sync(&id, &state).await {
         Ok(result) => {
             println!("[sync_route] Success. Logs:\n{}", result.logs);
-            Ok(())
+            result
         }
         Err(e) => {
             let msg = format!("Sync failed: {}", e);
             println!("[sync_route] {}", msg);
-            Err(msg)
+            return Err(msg);
         }
+    };
+    if !commit.unwrap_or(false) {
+        return Ok(None);
+    }
+    if result.fail_count > 0 {
+        return Err(format!(
+            "Not committing: {} file(s) failed to sync",
+            result.fail_count
+        ));
     }
+
+    let identity = configured_identity(&state).await?;
+    tokio::task::spawn_blocking(move || {
+        let repo = git2::Repository::open(&result.target_path).map_err(|e| e.to_string())?;
+        let signature = commit_signature(&repo, &identity)?;
+        SyncEngine::commit(&result, message.as_deref(), &signature).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -347,11 +448,11 @@ pub async fn preview_glob_matches(
     let mut matches = Vec::new();
 
     // Walk directory and match files
-    for entry in WalkDir::new(repo_path).into_iter().filter_entry(|e| {
-        // Skip hidden directories and common ignore patterns
-        let name = e.file_name().to_string_lossy();
-        !name.starts_with('.') && name != "node_modules" && name != "target" && name != "dist"
-    }) {
+    // Skip hidden directories and common ignore patterns, like a sync does
+    for entry in WalkDir::new(repo_path)
+        .into_iter()
+        .filter_entry(|e| !DiffEngine::is_excluded(&e.file_name().to_string_lossy()))
+    {
         if let Ok(entry) = entry {
             if entry.file_type().is_file() {
                 if let Ok(relative) = entry.path().strip_prefix(repo_path) {
@@ -378,6 +479,19 @@ pub async fn preview_glob_matches(
     Ok(GlobMatchResult { matches, total })
 }
 
+/// Ids of a route group and all of its sub-groups
+pub(crate) fn descendant_route_group_ids(
+    groups: &[route_groups::Model],
+    root_id: &str,
+) -> HashSet<String> {
+    descendant_group_ids(
+        groups
+            .iter()
+            .map(|g| (g.id.as_str(), g.parent_id.as_deref())),
+        root_id,
+    )
+}
+
 fn build_tree(
     groups: &[route_groups::Model],
     routes: &[routes::Model],
@@ -397,6 +511,8 @@ fn build_tree(
                     group_id: r.group_id.clone(),
                     source_id: r.main_repo_id.clone(),
                     target_id: r.slave_repo_id.clone(),
+                    read_only: false,
+                    archived: r.archived_at.is_some(),
                 })
                 .collect();
 
@@ -419,6 +535,8 @@ fn build_tree(
                 group_id: None,
                 source_id: r.main_repo_id.clone(),
                 target_id: r.slave_repo_id.clone(),
+                read_only: false,
+                archived: r.archived_at.is_some(),
             })
             .collect();
 
@@ -434,3 +552,38 @@ fn build_tree(
 
     nodes
 }
+
+fn build_repo_defined_group(defined: &[RepoDefinedRoute]) -> RouteGroupNode {
+    // One child group per repository that declares routes, in first-seen order
+    let mut children: Vec<RouteGroupNode> = Vec::new();
+
+    for entry in defined {
+        let node = RouteNode {
+            id: entry.route.id.clone(),
+            name: entry.route.name.clone(),
+            group_id: None,
+            source_id: entry.route.main_repo_id.clone(),
+            target_id: entry.route.slave_repo_id.clone(),
+            read_only: true,
+            archived: false,
+        };
+
+        let group_id = format!("route_repo_defined_{}", entry.repo_id);
+        match children.iter_mut().find(|c| c.id == group_id) {
+            Some(group) => group.routes.push(node),
+            None => children.push(RouteGroupNode {
+                id: group_id,
+                name: entry.repo_name.clone(),
+                children: vec![],
+                routes: vec![node],
+            }),
+        }
+    }
+
+    RouteGroupNode {
+        id: "route_repo_defined_virtual".to_string(),
+        name: "Repository Defined".to_string(),
+        children,
+        routes: vec![],
+    }
+}
@@ -0,0 +1,265 @@
+use crate::commands::route::{descendant_route_group_ids, MappingRule};
+use crate::core::mapping_history::MappingHistory;
+use crate::core::route_bundle::{
+    group_chain, route_group_parents, BundleFormat, BundledRoute, RepoIndex, RepoRef, RouteBundle,
+};
+use crate::database::entities::{repo_groups, repositories, route_groups, routes};
+use crate::database::manager::DatabaseManager;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RouteExportScope {
+    Route { id: String },
+    Group { id: String },
+    All,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportedRoute {
+    pub name: String,
+    pub route_id: String,
+    pub source_repo_id: Option<String>,
+    pub target_repo_id: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RouteImportReport {
+    pub imported: usize,
+    pub routes: Vec<ImportedRoute>,
+}
+
+/// Export one route, a route group (including sub-groups) or all routes to a YAML/JSON bundle
+#[tauri::command(rename_all = "snake_case")]
+pub async fn export_routes(
+    scope: RouteExportScope,
+    file_path: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<usize, String> {
+    let db = &state.connection;
+
+    let all_groups = route_groups::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let all_routes = routes::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let selected: Vec<&routes::Model> = match &scope {
+        RouteExportScope::Route { id } => all_routes.iter().filter(|r| &r.id == id).collect(),
+        RouteExportScope::Group { id } => {
+            let group_ids = descendant_route_group_ids(&all_groups, id);
+            all_routes
+                .iter()
+                .filter(|r| r.group_id.as_ref().is_some_and(|g| group_ids.contains(g)))
+                .collect()
+        }
+        RouteExportScope::All => all_routes.iter().collect(),
+    };
+
+    if selected.is_empty() {
+        return Err("No routes to export".to_string());
+    }
+
+    let repo_groups = repo_groups::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let repos = repositories::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let index = RepoIndex::new(&repo_groups, repos);
+    let parents = route_group_parents(&all_groups);
+
+    let mut bundled = Vec::new();
+    for route in selected {
+        let mappings: Vec<MappingRule> = match route.mappings.as_deref() {
+            Some(json) if !json.trim().is_empty() => serde_json::from_str(json)
+                .map_err(|e| format!("Invalid mappings in route '{}': {}", route.name, e))?,
+            _ => vec![],
+        };
+
+        bundled.push(BundledRoute {
+            name: route.name.clone(),
+            description: route.description.clone(),
+            group: group_chain(&parents, route.group_id.as_ref()),
+            source: route
+                .main_repo_id
+                .as_deref()
+                .and_then(|id| index.reference(id)),
+            target: route
+                .slave_repo_id
+                .as_deref()
+                .and_then(|id| index.reference(id)),
+            mappings,
+        });
+    }
+
+    let count = bundled.len();
+    let path = Path::new(&file_path);
+    let content = RouteBundle::new(bundled)
+        .render(BundleFormat::from_path(path))
+        .map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| format!("Failed to write bundle: {}", e))?;
+
+    Ok(count)
+}
+
+/// Import a route bundle, resolving repositories by remote URL (falling back to relative path).
+/// Route groups from the bundle are recreated below `parent_group_id` (or at the root).
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_routes(
+    file_path: String,
+    parent_group_id: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<RouteImportReport, String> {
+    let path = Path::new(&file_path);
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let bundle =
+        RouteBundle::parse(&content, BundleFormat::from_path(path)).map_err(|e| e.to_string())?;
+
+    let db = &state.connection;
+    let repo_groups = repo_groups::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let repos = repositories::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let index = RepoIndex::new(&repo_groups, repos);
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    let mut group_cache: HashMap<Vec<String>, String> = HashMap::new();
+    let mut imported = Vec::new();
+
+    for route in bundle.routes {
+        let mut warnings = Vec::new();
+        let mut resolve = |label: &str, reference: &Option<RepoRef>| -> Option<String> {
+            let reference = reference.as_ref()?;
+            match index.resolve(reference) {
+                Some(repo) => Some(repo.id.clone()),
+                None => {
+                    warnings.push(format!(
+                        "{} repository not found: {}",
+                        label,
+                        reference.describe()
+                    ));
+                    None
+                }
+            }
+        };
+        let source_repo_id = resolve("Source", &route.source);
+        let target_repo_id = resolve("Target", &route.target);
+
+        let group_id = ensure_route_group_path(
+            &txn,
+            parent_group_id.clone(),
+            &route.group,
+            &mut group_cache,
+        )
+        .await?;
+
+        let mappings = serde_json::to_string(&route.mappings).map_err(|e| e.to_string())?;
+        let route_id = Uuid::new_v4().to_string();
+
+        let active = routes::ActiveModel {
+            id: Set(route_id.clone()),
+            name: Set(route.name.clone()),
+            description: Set(route.description),
+            main_repo_id: Set(source_repo_id.clone()),
+            slave_repo_id: Set(target_repo_id.clone()),
+            group_id: Set(group_id),
+            mappings: Set(Some(mappings.clone())),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        active.insert(&txn).await.map_err(|e| e.to_string())?;
+        MappingHistory::record(
+            &txn,
+            &route_id,
+            None,
+            Some(mappings),
+            Some("Imported".to_string()),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        imported.push(ImportedRoute {
+            name: route.name,
+            route_id,
+            source_repo_id,
+            target_repo_id,
+            warnings,
+        });
+    }
+
+    txn.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(RouteImportReport {
+        imported: imported.len(),
+        routes: imported,
+    })
+}
+
+/// Find or create the chain of route groups named by `segments` below `parent_id`
+async fn ensure_route_group_path(
+    txn: &DatabaseTransaction,
+    parent_id: Option<String>,
+    segments: &[String],
+    cache: &mut HashMap<Vec<String>, String>,
+) -> Result<Option<String>, String> {
+    let mut current_parent_id = parent_id;
+
+    for depth in 0..segments.len() {
+        let key = segments[..=depth].to_vec();
+        if let Some(id) = cache.get(&key) {
+            current_parent_id = Some(id.clone());
+            continue;
+        }
+
+        let segment = &segments[depth];
+        let existing = route_groups::Entity::find()
+            .filter(route_groups::Column::Name.eq(segment))
+            .filter(match &current_parent_id {
+                Some(pid) => route_groups::Column::ParentId.eq(pid),
+                None => route_groups::Column::ParentId.is_null(),
+            })
+            .one(txn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let id = match existing {
+            Some(group) => group.id,
+            None => {
+                let new_id = Uuid::new_v4().to_string();
+                let new_group = route_groups::ActiveModel {
+                    id: Set(new_id.clone()),
+                    name: Set(segment.clone()),
+                    parent_id: Set(current_parent_id.clone()),
+                    sort_order: Set(0),
+                };
+                new_group.insert(txn).await.map_err(|e| e.to_string())?;
+                new_id
+            }
+        };
+
+        cache.insert(key, id.clone());
+        current_parent_id = Some(id);
+    }
+
+    Ok(current_parent_id)
+}
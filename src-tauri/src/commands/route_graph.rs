@@ -0,0 +1,349 @@
+use crate::commands::route::descendant_route_group_ids;
+use crate::core::repo_routes::RepoRouteRegistry;
+use crate::core::route_graph::RouteGraph;
+use crate::core::sync::SyncEngine;
+use crate::database::entities::{repositories, route_dependencies, route_groups, routes};
+use crate::database::manager::DatabaseManager;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct RouteDependencies {
+    /// Routes that must be synced before this one
+    pub depends_on: Vec<String>,
+    /// Routes that wait for this one
+    pub dependents: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct RouteSyncOutcome {
+    pub route_id: String,
+    pub name: String,
+    pub status: String, // "success" | "failed" | "skipped"
+    pub success_count: usize,
+    pub fail_count: usize,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GroupSyncReport {
+    /// Route ids in the order they were processed
+    pub order: Vec<String>,
+    pub results: Vec<RouteSyncOutcome>,
+}
+
+#[derive(Serialize)]
+pub struct GraphRepository {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct GraphRoute {
+    pub id: String,
+    pub name: String,
+    pub group_id: Option<String>,
+    pub source_id: Option<String>,
+    pub target_id: Option<String>,
+    pub read_only: bool,
+}
+
+#[derive(Serialize)]
+pub struct GraphDependency {
+    pub route_id: String,
+    pub depends_on_route_id: String,
+}
+
+/// Everything needed to draw how code flows between repositories:
+/// repositories are nodes, routes are edges source -> target, dependencies link routes.
+#[derive(Serialize)]
+pub struct RouteGraphView {
+    pub repositories: Vec<GraphRepository>,
+    pub routes: Vec<GraphRoute>,
+    pub dependencies: Vec<GraphDependency>,
+}
+
+/// Replace the list of routes `route_id` depends on. Rejected if it would create a cycle.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_route_dependencies(
+    route_id: String,
+    depends_on: Vec<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    let db = &state.connection;
+    RepoRouteRegistry::ensure_mutable(&route_id)?;
+
+    let all_routes = routes::Entity::find()
+        .order_by_asc(routes::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let names: HashMap<&str, &str> = all_routes
+        .iter()
+        .map(|r| (r.id.as_str(), r.name.as_str()))
+        .collect();
+
+    if !names.contains_key(route_id.as_str()) {
+        return Err("Route not found".to_string());
+    }
+
+    let mut wanted: Vec<String> = Vec::new();
+    for dependency in depends_on {
+        if dependency == route_id {
+            return Err("A route cannot depend on itself".to_string());
+        }
+        if RepoRouteRegistry::is_repo_defined(&dependency) {
+            return Err(
+                "Routes defined in a repository cannot be used as dependencies".to_string(),
+            );
+        }
+        if !names.contains_key(dependency.as_str()) {
+            return Err(format!("Route {} not found", dependency));
+        }
+        if !wanted.contains(&dependency) {
+            wanted.push(dependency);
+        }
+    }
+
+    // Validate against the full graph with this route's edges replaced
+    let mut edges: Vec<(String, String)> = load_edges(db)
+        .await?
+        .into_iter()
+        .filter(|(from, _)| from != &route_id)
+        .collect();
+    edges.extend(wanted.iter().map(|d| (route_id.clone(), d.clone())));
+
+    let graph = RouteGraph::new(all_routes.iter().map(|r| r.id.clone()).collect(), &edges);
+    if let Err(cycle) = graph.topological_order() {
+        let chain: Vec<&str> = cycle
+            .iter()
+            .map(|id| names.get(id.as_str()).copied().unwrap_or(id.as_str()))
+            .collect();
+        return Err(format!("Dependency cycle: {}", chain.join(" -> ")));
+    }
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    route_dependencies::Entity::delete_many()
+        .filter(route_dependencies::Column::RouteId.eq(&route_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for dependency in wanted {
+        route_dependencies::ActiveModel {
+            route_id: Set(route_id.clone()),
+            depends_on_route_id: Set(dependency),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    txn.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_route_dependencies(
+    route_id: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<RouteDependencies, String> {
+    let edges = load_edges(&state.connection).await?;
+
+    Ok(RouteDependencies {
+        depends_on: edges
+            .iter()
+            .filter(|(from, _)| from == &route_id)
+            .map(|(_, to)| to.clone())
+            .collect(),
+        dependents: edges
+            .iter()
+            .filter(|(_, to)| to == &route_id)
+            .map(|(from, _)| from.clone())
+            .collect(),
+    })
+}
+
+/// Sync every route of a group in dependency order.
+/// Routes whose dependencies failed (or were skipped) are skipped; with `stop_on_error`
+/// everything after the first failure is skipped.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn sync_route_group(
+    group_id: String,
+    recursive: Option<bool>,
+    stop_on_error: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<GroupSyncReport, String> {
+    let db = &state.connection;
+
+    let group_ids: HashSet<String> = if recursive.unwrap_or(true) {
+        let groups = route_groups::Entity::find()
+            .all(db)
+            .await
+            .map_err(|e| e.to_string())?;
+        descendant_route_group_ids(&groups, &group_id)
+    } else {
+        HashSet::from([group_id])
+    };
+
+    let group_routes: Vec<routes::Model> = routes::Entity::find()
+        .order_by_asc(routes::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|r| r.group_id.as_ref().is_some_and(|g| group_ids.contains(g)))
+        .collect();
+    let names: HashMap<String, String> = group_routes
+        .iter()
+        .map(|r| (r.id.clone(), r.name.clone()))
+        .collect();
+
+    // Dependencies on routes outside the group are assumed to be satisfied
+    let edges = load_edges(db).await?;
+    let graph = RouteGraph::new(group_routes.iter().map(|r| r.id.clone()).collect(), &edges);
+    let order = graph.topological_order().map_err(|cycle| {
+        let chain: Vec<&str> = cycle
+            .iter()
+            .map(|id| names.get(id).map(|n| n.as_str()).unwrap_or(id.as_str()))
+            .collect();
+        format!("Dependency cycle: {}", chain.join(" -> "))
+    })?;
+
+    let stop_on_error = stop_on_error.unwrap_or(false);
+    let mut not_ok: HashSet<String> = HashSet::new();
+    let mut results = Vec::with_capacity(order.len());
+
+    for route_id in &order {
+        let name = names.get(route_id).cloned().unwrap_or_default();
+        let blocked_by = graph
+            .dependencies_of(route_id)
+            .iter()
+            .find(|d| not_ok.contains(*d));
+
+        if (stop_on_error && !not_ok.is_empty()) || blocked_by.is_some() {
+            let message = match blocked_by {
+                Some(dependency) => format!(
+                    "Skipped: dependency '{}' did not sync",
+                    names.get(dependency).map(|n| n.as_str()).unwrap_or("?")
+                ),
+                None => "Skipped after an earlier failure".to_string(),
+            };
+            not_ok.insert(route_id.clone());
+            results.push(RouteSyncOutcome {
+                route_id: route_id.clone(),
+                name,
+                status: "skipped".to_string(),
+                success_count: 0,
+                fail_count: 0,
+                message: Some(message),
+            });
+            continue;
+        }
+
+        println!("[sync_route_group] Syncing route {} ({})", name, route_id);
+        let outcome = match SyncEngine::execute_sync(route_id, &state).await {
+            Ok(result) if result.fail_count == 0 => RouteSyncOutcome {
+                route_id: route_id.clone(),
+                name,
+                status: "success".to_string(),
+                success_count: result.success_count,
+                fail_count: 0,
+                message: None,
+            },
+            Ok(result) => RouteSyncOutcome {
+                route_id: route_id.clone(),
+                name,
+                status: "failed".to_string(),
+                success_count: result.success_count,
+                fail_count: result.fail_count,
+                message: Some(result.logs),
+            },
+            Err(e) => RouteSyncOutcome {
+                route_id: route_id.clone(),
+                name,
+                status: "failed".to_string(),
+                success_count: 0,
+                fail_count: 0,
+                message: Some(format!("Sync failed: {}", e)),
+            },
+        };
+
+        if outcome.status != "success" {
+            not_ok.insert(route_id.clone());
+        }
+        results.push(outcome);
+    }
+
+    Ok(GroupSyncReport { order, results })
+}
+
+#[tauri::command]
+pub async fn get_route_graph(state: State<'_, DatabaseManager>) -> Result<RouteGraphView, String> {
+    let db = &state.connection;
+
+    let repositories = repositories::Entity::find()
+        .order_by_asc(repositories::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|r| GraphRepository {
+            id: r.id,
+            name: r.name,
+        })
+        .collect();
+
+    let stored = routes::Entity::find()
+        .order_by_asc(routes::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let repo_defined = RepoRouteRegistry::load_all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let routes = stored
+        .into_iter()
+        .map(|r| (r, false))
+        .chain(repo_defined.into_iter().map(|d| (d.route, true)))
+        .map(|(r, read_only)| GraphRoute {
+            id: r.id,
+            name: r.name,
+            group_id: r.group_id,
+            source_id: r.main_repo_id,
+            target_id: r.slave_repo_id,
+            read_only,
+        })
+        .collect();
+
+    let dependencies = load_edges(db)
+        .await?
+        .into_iter()
+        .map(|(route_id, depends_on_route_id)| GraphDependency {
+            route_id,
+            depends_on_route_id,
+        })
+        .collect();
+
+    Ok(RouteGraphView {
+        repositories,
+        routes,
+        dependencies,
+    })
+}
+
+async fn load_edges(db: &sea_orm::DatabaseConnection) -> Result<Vec<(String, String)>, String> {
+    Ok(route_dependencies::Entity::find()
+        .order_by_asc(route_dependencies::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|d| (d.route_id, d.depends_on_route_id))
+        .collect())
+}
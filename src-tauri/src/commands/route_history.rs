@@ -0,0 +1,106 @@
+use crate::core::mapping_history::{parse_mappings, MappingDiff, MappingHistory};
+use crate::core::repo_routes::RepoRouteRegistry;
+use crate::database::entities::{route_mapping_revisions, routes};
+use crate::database::manager::DatabaseManager;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use tauri::State;
+
+/// List a route's mapping revisions, newest first
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_route_revisions(
+    route_id: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<route_mapping_revisions::Model>, String> {
+    let db = &state.connection;
+    route_mapping_revisions::Entity::find()
+        .filter(route_mapping_revisions::Column::RouteId.eq(route_id))
+        .order_by_desc(route_mapping_revisions::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Compare the mappings of two revisions. Without `to_revision_id` the first revision
+/// is compared against the route's current mappings.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn compare_route_revisions(
+    from_revision_id: i32,
+    to_revision_id: Option<i32>,
+    state: State<'_, DatabaseManager>,
+) -> Result<MappingDiff, String> {
+    let db = &state.connection;
+    let from = find_revision(&state, from_revision_id).await?;
+
+    let to_mappings = match to_revision_id {
+        Some(id) => {
+            let to = find_revision(&state, id).await?;
+            if to.route_id != from.route_id {
+                return Err("Revisions belong to different routes".to_string());
+            }
+            to.new_mappings
+        }
+        None => {
+            routes::Entity::find_by_id(&from.route_id)
+                .one(db)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or("Route not found")?
+                .mappings
+        }
+    };
+
+    let old_rules = parse_mappings(from.new_mappings.as_deref()).map_err(|e| e.to_string())?;
+    let new_rules = parse_mappings(to_mappings.as_deref()).map_err(|e| e.to_string())?;
+    Ok(MappingDiff::compute(&old_rules, &new_rules))
+}
+
+/// Restore the mappings saved by a revision. The restore itself is recorded as a new revision.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn restore_route_revision(
+    revision_id: i32,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    let db = &state.connection;
+    let revision = find_revision(&state, revision_id).await?;
+    RepoRouteRegistry::ensure_mutable(&revision.route_id)?;
+
+    let route = routes::Entity::find_by_id(&revision.route_id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Route not found")?;
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    let previous = route.mappings.clone();
+
+    let mut active: routes::ActiveModel = route.into();
+    active.mappings = Set(revision.new_mappings.clone());
+    active.updated_at = Set(chrono::Utc::now().naive_utc());
+    active.update(&txn).await.map_err(|e| e.to_string())?;
+
+    MappingHistory::record(
+        &txn,
+        &revision.route_id,
+        previous,
+        revision.new_mappings,
+        Some(format!("Restored revision #{}", revision.id)),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    txn.commit().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn find_revision(
+    state: &DatabaseManager,
+    id: i32,
+) -> Result<route_mapping_revisions::Model, String> {
+    route_mapping_revisions::Entity::find_by_id(id)
+        .one(&state.connection)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Revision #{} not found", id))
+}
@@ -0,0 +1,125 @@
+use crate::commands::route::RouteNode;
+use crate::core::repo_routes::RepoRouteRegistry;
+use crate::core::route_impact::{normalize_relative_path, PathImpact, RouteImpactAnalyzer};
+use crate::database::entities::repositories::{self, RoutesAsSource, RoutesAsTarget};
+use crate::database::entities::routes;
+use crate::database::manager::DatabaseManager;
+use sea_orm::{EntityTrait, ModelTrait};
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct RepositoryRoutes {
+    /// Routes reading from the repository
+    pub as_source: Vec<RouteNode>,
+    /// Routes writing into the repository
+    pub as_target: Vec<RouteNode>,
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_routes_by_repository(
+    repo_id: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<RepositoryRoutes, String> {
+    let db = &state.connection;
+    let repo = repositories::Entity::find_by_id(&repo_id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Repository not found")?;
+
+    let mut as_source: Vec<RouteNode> = repo
+        .find_linked(RoutesAsSource)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|r| route_node(r, false))
+        .collect();
+    let mut as_target: Vec<RouteNode> = repo
+        .find_linked(RoutesAsTarget)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|r| route_node(r, false))
+        .collect();
+
+    for defined in RepoRouteRegistry::load_all(db)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        if defined.route.main_repo_id.as_deref() == Some(repo_id.as_str()) {
+            as_source.push(route_node(&defined.route, true));
+        }
+        if defined.route.slave_repo_id.as_deref() == Some(repo_id.as_str()) {
+            as_target.push(route_node(&defined.route, true));
+        }
+    }
+
+    as_source.sort_by(|a, b| a.name.cmp(&b.name));
+    as_target.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(RepositoryRoutes {
+        as_source,
+        as_target,
+    })
+}
+
+/// Report every route and rule that would carry `path` out of the repository and where it lands.
+/// `path` may be relative to the repository or an absolute path inside it.
+/// Copies are followed through further routes unless `transitive` is false.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn analyze_path_impact(
+    repo_id: String,
+    path: String,
+    transitive: Option<bool>,
+    state: State<'_, DatabaseManager>,
+) -> Result<PathImpact, String> {
+    let db = &state.connection;
+    let repo = repositories::Entity::find_by_id(&repo_id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Repository not found")?;
+
+    let relative = match Path::new(&path).strip_prefix(&repo.local_path) {
+        Ok(inside) => inside.to_string_lossy().to_string(),
+        Err(_) => path,
+    };
+    let relative = normalize_relative_path(&relative);
+    if relative.is_empty() {
+        return Err("Path must point to a file inside the repository".to_string());
+    }
+
+    let mut all_routes = routes::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    all_routes.extend(
+        RepoRouteRegistry::load_all(db)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|d| d.route),
+    );
+
+    Ok(RouteImpactAnalyzer::trace(
+        &all_routes,
+        &repo_id,
+        &relative,
+        transitive.unwrap_or(true),
+    ))
+}
+
+fn route_node(route: &routes::Model, read_only: bool) -> RouteNode {
+    RouteNode {
+        id: route.id.clone(),
+        name: route.name.clone(),
+        group_id: route.group_id.clone(),
+        source_id: route.main_repo_id.clone(),
+        target_id: route.slave_repo_id.clone(),
+        read_only,
+        archived: route.archived_at.is_some(),
+    }
+}
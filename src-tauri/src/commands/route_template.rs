@@ -0,0 +1,259 @@
+use crate::commands::route::MappingRule;
+use crate::core::mapping_history::MappingHistory;
+use crate::core::repo_routes::find_route;
+use crate::core::route_template::TemplateEngine;
+use crate::database::entities::{route_templates, routes};
+use crate::database::manager::DatabaseManager;
+use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set, TransactionTrait};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+use uuid::Uuid;
+
+#[derive(Serialize)]
+pub struct RouteTemplateInfo {
+    #[serde(flatten)]
+    pub template: route_templates::Model,
+    /// Placeholder names used by the template, e.g. `["module"]`
+    pub variables: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn list_route_templates(
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<RouteTemplateInfo>, String> {
+    let db = &state.connection;
+    let templates = route_templates::Entity::find()
+        .order_by_asc(route_templates::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(templates
+        .into_iter()
+        .map(|template| RouteTemplateInfo {
+            variables: template_variables(&template),
+            template,
+        })
+        .collect())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_route_template(
+    name: String,
+    description: Option<String>,
+    route_name: String,
+    route_description: Option<String>,
+    source_id: Option<String>,
+    target_id: Option<String>,
+    mappings: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<String, String> {
+    let db = &state.connection;
+    validate_mappings(mappings.as_deref())?;
+
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().naive_utc();
+    let active = route_templates::ActiveModel {
+        id: Set(id.clone()),
+        name: Set(name),
+        description: Set(description),
+        route_name: Set(route_name),
+        route_description: Set(route_description),
+        main_repo_id: Set(source_id),
+        slave_repo_id: Set(target_id),
+        mappings: Set(mappings),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    active.insert(db).await.map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+/// Save an existing route as a template; placeholders can be added by editing it afterwards
+#[tauri::command(rename_all = "snake_case")]
+pub async fn create_template_from_route(
+    route_id: String,
+    name: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<String, String> {
+    let db = &state.connection;
+    let route = find_route(db, &route_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Route not found")?;
+
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().naive_utc();
+    let active = route_templates::ActiveModel {
+        id: Set(id.clone()),
+        name: Set(name),
+        description: Set(None),
+        route_name: Set(route.name),
+        route_description: Set(route.description),
+        main_repo_id: Set(route.main_repo_id),
+        slave_repo_id: Set(route.slave_repo_id),
+        mappings: Set(route.mappings),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    active.insert(db).await.map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_route_template(
+    id: String,
+    name: String,
+    description: Option<String>,
+    route_name: String,
+    route_description: Option<String>,
+    source_id: Option<String>,
+    target_id: Option<String>,
+    mappings: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    let db = &state.connection;
+    validate_mappings(mappings.as_deref())?;
+
+    let existing = route_templates::Entity::find_by_id(&id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(model) = existing {
+        let mut active: route_templates::ActiveModel = model.into();
+        active.name = Set(name);
+        active.description = Set(description);
+        active.route_name = Set(route_name);
+        active.route_description = Set(route_description);
+        active.main_repo_id = Set(source_id);
+        active.slave_repo_id = Set(target_id);
+        active.mappings = Set(mappings);
+        active.updated_at = Set(chrono::Utc::now().naive_utc());
+        active.update(db).await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_route_template(
+    id: String,
+    state: State<'_, DatabaseManager>,
+) -> Result<(), String> {
+    let db = &state.connection;
+    route_templates::Entity::delete_by_id(id)
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Create one route per entry of `instances`, each entry mapping placeholder names to values.
+/// `source_id` / `target_id` override the repositories stored in the template.
+/// All routes are created in a single transaction; any missing variable aborts the whole batch.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn instantiate_route_template(
+    template_id: String,
+    instances: Vec<HashMap<String, String>>,
+    group_id: Option<String>,
+    source_id: Option<String>,
+    target_id: Option<String>,
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<String>, String> {
+    let db = &state.connection;
+    let template = route_templates::Entity::find_by_id(&template_id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Template not found")?;
+
+    let rules: Vec<MappingRule> = match template.mappings.as_deref() {
+        Some(json) if !json.trim().is_empty() => {
+            serde_json::from_str(json).map_err(|e| e.to_string())?
+        }
+        _ => vec![],
+    };
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    let mut created = Vec::new();
+
+    for (index, values) in instances.iter().enumerate() {
+        let render = |text: &str| {
+            TemplateEngine::render(text, values)
+                .map_err(|e| format!("Instance {}: {}", index + 1, e))
+        };
+
+        let name = render(&template.route_name)?;
+        let description = template
+            .route_description
+            .as_deref()
+            .map(render)
+            .transpose()?;
+        let mappings = rules
+            .iter()
+            .map(|rule| {
+                Ok(MappingRule {
+                    source: render(&rule.source)?,
+                    target: render(&rule.target)?,
+                    mode: rule.mode.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mappings = serde_json::to_string(&mappings).map_err(|e| e.to_string())?;
+        let id = Uuid::new_v4().to_string();
+        let active = routes::ActiveModel {
+            id: Set(id.clone()),
+            name: Set(name),
+            description: Set(description),
+            main_repo_id: Set(source_id.clone().or(template.main_repo_id.clone())),
+            slave_repo_id: Set(target_id.clone().or(template.slave_repo_id.clone())),
+            group_id: Set(group_id.clone()),
+            mappings: Set(Some(mappings.clone())),
+            updated_at: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        active.insert(&txn).await.map_err(|e| e.to_string())?;
+        MappingHistory::record(
+            &txn,
+            &id,
+            None,
+            Some(mappings),
+            Some(format!("Created from template '{}'", template.name)),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        created.push(id);
+    }
+
+    txn.commit().await.map_err(|e| e.to_string())?;
+    Ok(created)
+}
+
+fn template_variables(template: &route_templates::Model) -> Vec<String> {
+    let mut text = template.route_name.clone();
+    if let Some(description) = &template.route_description {
+        text.push('\n');
+        text.push_str(description);
+    }
+    if let Some(mappings) = &template.mappings {
+        text.push('\n');
+        text.push_str(mappings);
+    }
+    TemplateEngine::variables(&text)
+}
+
+fn validate_mappings(mappings: Option<&str>) -> Result<(), String> {
+    if let Some(json) = mappings {
+        if !json.trim().is_empty() {
+            serde_json::from_str::<Vec<MappingRule>>(json)
+                .map_err(|e| format!("Invalid mappings: {}", e))?;
+        }
+    }
+    Ok(())
+}
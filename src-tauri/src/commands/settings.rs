@@ -80,6 +80,11 @@ pub async fn init_defaults(state: &DatabaseManager) -> Result<(), String> {
         ("git_path", "git"),
         ("preferred_editor", "code"),
         ("gemini_path", "gemini"),
+        ("fetch_interval_minutes", "15"),
+        ("fetch_concurrency", "2"),
+        ("repository_roots", ""),
+        ("status_snapshot_interval_minutes", "30"),
+        ("status_snapshot_retention_days", "30"),
     ];
 
     let db = &state.connection;
@@ -1,6 +1,9 @@
-use crate::database::entities::repositories;
+use crate::core::background_fetch::last_fetched;
+use crate::core::status_history::{self, StatusDuration};
+use crate::core::status_watcher::StatusWatcher;
+use crate::database::entities::{repo_status_snapshots, repositories};
 use crate::database::manager::DatabaseManager;
-use git2::{Repository, StatusOptions};
+use git2::{Repository, RepositoryState, Status, StatusOptions};
 use sea_orm::EntityTrait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -9,13 +12,29 @@ use tauri::State;
 
 use crate::core::dependency_scanner::DependencyScanner;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RepoStatus {
     pub id: String,
     pub name: String,
     pub path: String,
-    pub status: String, // "Clean", "Uncommitted", "Behind", "Ahead", "Diverged", "Error"
+    pub status: String, // "Clean", "Uncommitted", "Behind", "Ahead", "Diverged", "Conflicted", "Error"
     pub short_summary: String, // e.g., "3↓ 1↑", "2 M", "Clean"
+    pub branch: Option<String>,
+    /// Commit HEAD points at
+    pub head: Option<String>,
+    pub detached: bool,
+    pub ahead: usize,
+    pub behind: usize,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+    pub stash_count: usize,
+    /// Operation left in progress: "merge", "rebase", "cherry_pick", "revert" or "bisect"
+    pub operation: Option<String>,
+    /// When any remote was last fetched, from FETCH_HEAD
+    pub last_fetched_at: Option<String>,
+    pub auto_fetch: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -34,8 +53,14 @@ pub async fn scan_dependencies(
     DependencyScanner::scan(source, target).map_err(|e| e.to_string())
 }
 
+/// Status of every repository. Watched repositories are served from the watcher's cache;
+/// `refresh` rescans them all.
 #[tauri::command(rename_all = "snake_case")]
-pub async fn get_repos_status(state: State<'_, DatabaseManager>) -> Result<RepoStatusMap, String> {
+pub async fn get_repos_status(
+    refresh: Option<bool>,
+    state: State<'_, DatabaseManager>,
+    watcher: State<'_, StatusWatcher>,
+) -> Result<RepoStatusMap, String> {
     let db = &state.connection;
 
     // Fetch all repos
@@ -43,28 +68,26 @@ pub async fn get_repos_status(state: State<'_, DatabaseManager>) -> Result<RepoS
         .all(db)
         .await
         .map_err(|e| e.to_string())?;
+    watcher.track(&repos);
 
     let mut results = HashMap::new();
-
-    // Parallel processing using tokio::spawn could be done,
-    // but for simplicity and since we are in an async command, we can just iterate asyncly if we had async git ops.
-    // git2 is synchronous. Using `tokio::task::spawn_blocking` is better.
-
     let mut handles = vec![];
 
     for repo in repos {
-        let repo_path = repo.local_path.clone();
-        let repo_id = repo.id.clone();
-        let repo_name = repo.name.clone();
-
-        let handle =
-            tokio::task::spawn_blocking(move || check_repo_status(repo_id, repo_name, repo_path));
+        if !refresh.unwrap_or(false) {
+            if let Some(status) = watcher.cached(&repo.id) {
+                results.insert(status.id.clone(), status);
+                continue;
+            }
+        }
+        let handle = tokio::task::spawn_blocking(move || check_repo_status(&repo));
         handles.push(handle);
     }
 
     for handle in handles {
         match handle.await {
             Ok(status) => {
+                watcher.store(&status);
                 results.insert(status.id.clone(), status);
             }
             Err(e) => {
@@ -76,28 +99,70 @@ pub async fn get_repos_status(state: State<'_, DatabaseManager>) -> Result<RepoS
     Ok(RepoStatusMap { statuses: results })
 }
 
-fn check_repo_status(id: String, name: String, path_str: String) -> RepoStatus {
-    let path = Path::new(&path_str);
+/// Recorded status snapshots of a repository, oldest first. `since` and `until` take
+/// RFC 3339 timestamps or YYYY-MM-DD dates.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_repo_status_timeline(
+    repo_id: String,
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<u64>,
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<repo_status_snapshots::Model>, String> {
+    status_history::timeline(
+        &state.connection,
+        &repo_id,
+        since.as_deref(),
+        until.as_deref(),
+        limit,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Since when each repository has had its current status, and been dirty or diverged
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_repo_status_durations(
+    state: State<'_, DatabaseManager>,
+) -> Result<Vec<StatusDuration>, String> {
+    status_history::durations(&state.connection)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn check_repo_status(model: &repositories::Model) -> RepoStatus {
+    let mut status = RepoStatus {
+        id: model.id.clone(),
+        name: model.name.clone(),
+        path: model.local_path.clone(),
+        status: "Error".to_string(),
+        short_summary: String::new(),
+        branch: None,
+        head: None,
+        detached: false,
+        ahead: 0,
+        behind: 0,
+        staged: 0,
+        unstaged: 0,
+        untracked: 0,
+        conflicted: 0,
+        stash_count: 0,
+        operation: None,
+        last_fetched_at: None,
+        auto_fetch: model.auto_fetch,
+    };
+
+    let path = Path::new(&model.local_path);
     if !path.exists() {
-        return RepoStatus {
-            id,
-            name,
-            path: path_str,
-            status: "Error".to_string(),
-            short_summary: "Path missing".to_string(),
-        };
+        status.short_summary = "Path missing".to_string();
+        return status;
     }
 
-    let repo = match Repository::open(path) {
+    let mut repo = match Repository::open(path) {
         Ok(r) => r,
         Err(e) => {
-            return RepoStatus {
-                id,
-                name,
-                path: path_str,
-                status: "Error".to_string(),
-                short_summary: e.to_string(),
-            }
+            status.short_summary = e.to_string();
+            return status;
         }
     };
 
@@ -108,26 +173,64 @@ fn check_repo_status(id: String, name: String, path_str: String) -> RepoStatus {
     let statuses = match repo.statuses(Some(&mut opts)) {
         Ok(s) => s,
         Err(e) => {
-            return RepoStatus {
-                id,
-                name,
-                path: path_str,
-                status: "Error".to_string(),
-                short_summary: e.to_string(),
-            }
+            status.short_summary = e.to_string();
+            return status;
         }
     };
 
-    let has_changes = !statuses.is_empty();
     let change_count = statuses.len();
+    for entry in statuses.iter() {
+        let flags = entry.status();
+        if flags.is_conflicted() {
+            status.conflicted += 1;
+            continue;
+        }
+        if flags.intersects(
+            Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::INDEX_TYPECHANGE,
+        ) {
+            status.staged += 1;
+        }
+        if flags.intersects(
+            Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_RENAMED | Status::WT_TYPECHANGE,
+        ) {
+            status.unstaged += 1;
+        }
+        if flags.is_wt_new() {
+            status.untracked += 1;
+        }
+    }
+    drop(statuses);
+
+    if let Ok(head) = repo.head() {
+        status.detached = !head.is_branch();
+        status.head = head.target().map(|oid| oid.to_string());
+        status.branch = head
+            .is_branch()
+            .then(|| head.shorthand().map(String::from))
+            .flatten();
+    }
+    status.operation = operation_in_progress(repo.state());
+    status.last_fetched_at = last_fetched(&repo).map(|t| t.to_rfc3339());
+    let _ = repo.stash_foreach(|_, _, _| {
+        status.stash_count += 1;
+        true
+    });
 
     // Check ahead/behind
-    let (ahead, behind) = match get_ahead_behind(&repo) {
-        Ok((a, b)) => (a, b),
-        Err(_) => (0, 0),
-    };
+    let (ahead, behind) = get_ahead_behind(&repo).unwrap_or((0, 0));
+    status.ahead = ahead;
+    status.behind = behind;
 
-    let status_str = if ahead > 0 && behind > 0 {
+    let has_changes = change_count > 0;
+    // A bisect session only moves HEAD around; nothing is left to resolve
+    let interrupted = status.operation.as_deref().is_some_and(|op| op != "bisect");
+    let status_str = if status.conflicted > 0 || interrupted {
+        "Conflicted"
+    } else if ahead > 0 && behind > 0 {
         "Diverged"
     } else if ahead > 0 {
         "Ahead"
@@ -140,6 +243,12 @@ fn check_repo_status(id: String, name: String, path_str: String) -> RepoStatus {
     };
 
     let mut summary_parts = vec![];
+    if let Some(operation) = &status.operation {
+        summary_parts.push(operation.replace('_', "-"));
+    }
+    if status.detached {
+        summary_parts.push("detached".to_string());
+    }
     if behind > 0 {
         summary_parts.push(format!("{}↓", behind));
     }
@@ -150,19 +259,30 @@ fn check_repo_status(id: String, name: String, path_str: String) -> RepoStatus {
         summary_parts.push(format!("{} M", change_count));
     }
 
-    let short_summary = if summary_parts.is_empty() {
+    status.status = status_str.to_string();
+    status.short_summary = if summary_parts.is_empty() {
         "✓".to_string()
     } else {
         summary_parts.join(" ")
     };
+    status
+}
 
-    RepoStatus {
-        id,
-        name,
-        path: path_str,
-        status: status_str.to_string(),
-        short_summary,
-    }
+/// Name of the operation a repository is in the middle of
+fn operation_in_progress(state: RepositoryState) -> Option<String> {
+    let name = match state {
+        RepositoryState::Clean => return None,
+        RepositoryState::Merge => "merge",
+        RepositoryState::Revert | RepositoryState::RevertSequence => "revert",
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => "cherry_pick",
+        RepositoryState::Bisect => "bisect",
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge
+        | RepositoryState::ApplyMailbox
+        | RepositoryState::ApplyMailboxOrRebase => "rebase",
+    };
+    Some(name.to_string())
 }
 
 fn get_ahead_behind(repo: &Repository) -> Result<(usize, usize), git2::Error> {
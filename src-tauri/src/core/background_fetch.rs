@@ -0,0 +1,179 @@
+use crate::core::credentials::redact;
+use crate::core::git_remote::GitRemote;
+use crate::core::periodic::{int_setting, PeriodicTask};
+use crate::database::entities::repositories;
+use crate::database::manager::DatabaseManager;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::Duration;
+
+/// Minutes between background fetches of a repository; 0 turns background fetching off
+pub const FETCH_INTERVAL_SETTING: &str = "fetch_interval_minutes";
+/// Repositories fetched at the same time
+pub const FETCH_CONCURRENCY_SETTING: &str = "fetch_concurrency";
+
+const DEFAULT_INTERVAL_MINUTES: i64 = 15;
+const DEFAULT_CONCURRENCY: usize = 2;
+/// Longest wait before retrying a repository that keeps failing to fetch
+const MAX_BACKOFF_MINUTES: i64 = 24 * 60;
+
+/// Consecutive failed fetches of a repository and when to try it again
+struct FetchFailure {
+    count: u32,
+    retry_at: DateTime<Utc>,
+}
+
+type FetchFailures = Arc<Mutex<HashMap<String, FetchFailure>>>;
+
+/// Periodically fetches repositories with `auto_fetch` so ahead/behind stays current
+pub struct BackgroundFetcher {
+    db_manager: Arc<DatabaseManager>,
+    task: PeriodicTask,
+    failures: FetchFailures,
+}
+
+impl BackgroundFetcher {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            db_manager,
+            task: PeriodicTask::new("BackgroundFetch"),
+            failures: FetchFailures::default(),
+        }
+    }
+
+    /// Start the background loop
+    pub async fn start(&self) {
+        let db_manager = self.db_manager.clone();
+        let failures = self.failures.clone();
+        // Let startup finish before hitting the network
+        self.task
+            .start(Duration::from_secs(30), move || {
+                let db_manager = db_manager.clone();
+                let failures = failures.clone();
+                async move { fetch_due_repositories(&db_manager, &failures).await }
+            })
+            .await;
+    }
+
+    #[allow(dead_code)]
+    pub async fn stop(&self) {
+        self.task.stop().await;
+    }
+}
+
+/// When any remote of `repo` was last fetched, from the FETCH_HEAD git writes on every fetch
+pub(crate) fn last_fetched(repo: &Repository) -> Option<DateTime<Utc>> {
+    let modified = std::fs::metadata(repo.path().join("FETCH_HEAD"))
+        .and_then(|m| m.modified())
+        .ok()?;
+    Some(DateTime::<Utc>::from(modified))
+}
+
+/// Fetch every `auto_fetch` repository whose last fetch is older than the interval,
+/// skipping repositories still backing off from a failed fetch
+async fn fetch_due_repositories(
+    db_manager: &DatabaseManager,
+    failures: &FetchFailures,
+) -> Result<()> {
+    let db = &db_manager.connection;
+    let interval = int_setting(db, FETCH_INTERVAL_SETTING)
+        .await?
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+    if interval <= 0 {
+        return Ok(());
+    }
+    let concurrency = int_setting(db, FETCH_CONCURRENCY_SETTING)
+        .await?
+        .map(|n| n.clamp(1, 8) as usize)
+        .unwrap_or(DEFAULT_CONCURRENCY);
+
+    let repos = repositories::Entity::find()
+        .filter(repositories::Column::AutoFetch.eq(true))
+        .all(db)
+        .await?;
+
+    let permits = Arc::new(Semaphore::new(concurrency));
+    let now = Utc::now();
+    let due_before = now - chrono::Duration::minutes(interval);
+    let mut tasks = JoinSet::new();
+    for repo in repos {
+        let backing_off = failures
+            .lock()
+            .unwrap()
+            .get(&repo.id)
+            .is_some_and(|f| f.retry_at > now);
+        if backing_off {
+            continue;
+        }
+
+        let permits = permits.clone();
+        let failures = failures.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let id = repo.id.clone();
+            let name = repo.name.clone();
+            let outcome = tokio::task::spawn_blocking(move || fetch_if_due(&repo, due_before))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r);
+            let mut failures = failures.lock().unwrap();
+            match outcome {
+                Ok(()) => {
+                    failures.remove(&id);
+                }
+                Err(e) => {
+                    let count = failures.get(&id).map_or(0, |f| f.count) + 1;
+                    let backoff = backoff_minutes(interval, count);
+                    failures.insert(
+                        id,
+                        FetchFailure {
+                            count,
+                            retry_at: Utc::now() + chrono::Duration::minutes(backoff),
+                        },
+                    );
+                    eprintln!(
+                        "[BackgroundFetch] {}: {} (retrying in {} min)",
+                        name,
+                        redact(&e.to_string()),
+                        backoff
+                    );
+                }
+            }
+        });
+    }
+    while tasks.join_next().await.is_some() {}
+    Ok(())
+}
+
+/// Wait after the `count`th consecutive failure: the interval, doubled per further failure
+fn backoff_minutes(interval: i64, count: u32) -> i64 {
+    let doublings = count.saturating_sub(1).min(16);
+    interval
+        .saturating_mul(1 << doublings)
+        .min(MAX_BACKOFF_MINUTES)
+}
+
+fn fetch_if_due(repo: &repositories::Model, due_before: DateTime<Utc>) -> Result<()> {
+    if !Path::new(&repo.local_path).exists() {
+        return Ok(());
+    }
+    let fetched = Repository::open(&repo.local_path)
+        .ok()
+        .and_then(|r| last_fetched(&r));
+    if fetched.is_some_and(|t| t > due_before) {
+        return Ok(());
+    }
+
+    let result = GitRemote::open(repo)?.fetch(None)?;
+    if !result.refs_updated.is_empty() {
+        println!("[BackgroundFetch] {}: {}", repo.name, result.message);
+    }
+    Ok(())
+}
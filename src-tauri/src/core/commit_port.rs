@@ -0,0 +1,558 @@
+use crate::commands::route::MappingRule;
+use crate::core::diff::DiffEngine;
+use crate::core::git_history::diff_against_parent;
+use crate::core::provenance::Provenance;
+use anyhow::{anyhow, Result};
+use git2::build::CheckoutBuilder;
+use git2::{
+    ApplyLocation, ApplyOptions, Commit, Delta, Diff, FileMode, Patch, Repository, Signature, Sort,
+    StatusOptions,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PortedFile {
+    pub source_path: String,
+    pub target_path: Option<String>,
+    pub status: String, // "applied" | "partial" | "failed" | "unmapped"
+    /// Headers of hunks that did not apply
+    pub failed_hunks: Vec<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PortedCommit {
+    pub source_sha: String,
+    pub summary: String,
+    pub status: String, // "ported" | "partial" | "failed" | "empty"
+    pub target_sha: Option<String>,
+    pub files: Vec<PortedFile>,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PortReport {
+    pub results: Vec<PortedCommit>,
+    /// Porting stopped at a failed commit; later commits were not attempted
+    pub stopped: bool,
+}
+
+/// One changed file of a source commit, rewritten to target paths
+struct FileChange {
+    source_path: String,
+    target_path: String,
+    deleted: bool,
+    /// Unified diff against the target path; `None` for binary files
+    patch: Option<String>,
+    /// New content of binary files
+    blob: Option<Vec<u8>>,
+    hunk_headers: Vec<String>,
+}
+
+/// Ports commits between repositories as patches: each commit's diff is rewritten through a
+/// route's mappings and applied to the target, keeping the original author and message.
+pub struct CommitPorter<'a> {
+    pub source: &'a Repository,
+    pub target: &'a Repository,
+    pub mappings: &'a [MappingRule],
+    pub provenance: Provenance,
+    pub committer: Signature<'static>,
+    /// Commit whatever applied instead of stopping at the first conflicting hunk
+    pub allow_partial: bool,
+}
+
+impl CommitPorter<'_> {
+    /// Port `shas` oldest first. Stops at the first commit that cannot be applied cleanly
+    /// (or at all, with `allow_partial`), since later commits usually build on it.
+    /// A commit that errors while being applied is reported as failed and the target restored.
+    pub fn port(&self, shas: &[String]) -> Result<PortReport> {
+        let mut selected = HashSet::new();
+        let mut walk = self.source.revwalk()?;
+        for sha in shas {
+            let commit = self
+                .source
+                .revparse_single(sha)
+                .and_then(|obj| obj.peel_to_commit())
+                .map_err(|e| anyhow!("Cannot resolve '{}' in the source: {}", sha, e))?;
+            selected.insert(commit.id());
+            walk.push(commit.id())?;
+        }
+        // Parents before children, whatever order the commits were picked in
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        let mut commits = Vec::new();
+        for oid in walk {
+            let oid = oid?;
+            if selected.contains(&oid) {
+                commits.push(self.source.find_commit(oid)?);
+            }
+        }
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(false);
+        if !self.target.statuses(Some(&mut opts))?.is_empty() {
+            return Err(anyhow!(
+                "Target repository has uncommitted changes; commit or stash them first"
+            ));
+        }
+
+        let mut report = PortReport::default();
+        for commit in &commits {
+            let result = self.port_commit(commit).unwrap_or_else(|e| PortedCommit {
+                source_sha: commit.id().to_string(),
+                summary: commit.summary().unwrap_or("").to_string(),
+                status: "failed".to_string(),
+                target_sha: None,
+                files: Vec::new(),
+                message: Some(e.to_string()),
+            });
+            let failed = result.status == "failed";
+            report.results.push(result);
+            if failed {
+                report.stopped = true;
+                break;
+            }
+        }
+        Ok(report)
+    }
+
+    fn port_commit(&self, commit: &Commit) -> Result<PortedCommit> {
+        let mut result = PortedCommit {
+            source_sha: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            status: "failed".to_string(),
+            target_sha: None,
+            files: Vec::new(),
+            message: None,
+        };
+        if commit.parent_count() > 1 {
+            result.message = Some("Merge commits cannot be ported".to_string());
+            return Ok(result);
+        }
+
+        // 1. Rewrite every changed file to its target path
+        let diff = diff_against_parent(self.source, commit, None)?;
+        let mut changes = Vec::new();
+        for index in 0..diff.deltas().len() {
+            match self.rewrite(&diff, index)? {
+                Ok(change) => changes.push(change),
+                Err(unmapped) => result.files.push(unmapped),
+            }
+        }
+
+        // 2. Dry run: find the hunks that do not apply
+        let mut failed_hunks: Vec<Vec<usize>> = Vec::new();
+        for change in &changes {
+            failed_hunks.push(self.failing_hunks(change)?);
+        }
+        let any_failed = failed_hunks.iter().any(|f| !f.is_empty());
+        if any_failed && !self.allow_partial {
+            for (change, failed) in changes.iter().zip(&failed_hunks) {
+                result.files.push(self.file_result(change, failed, false));
+            }
+            result.message = Some("Some hunks do not apply to the target".to_string());
+            return Ok(result);
+        }
+
+        // 3. Apply what applies; a failure puts the touched files back as they were
+        let workdir = self
+            .target
+            .workdir()
+            .ok_or_else(|| anyhow!("Target repository has no working tree"))?
+            .to_path_buf();
+        let existing: HashSet<String> = changes
+            .iter()
+            .filter(|c| workdir.join(&c.target_path).exists())
+            .map(|c| c.target_path.clone())
+            .collect();
+        if let Err(e) =
+            self.apply_and_commit(commit, &changes, &failed_hunks, &workdir, &mut result)
+        {
+            self.restore(&changes, &existing, &workdir)?;
+            result.status = "failed".to_string();
+            result.target_sha = None;
+            result.message = Some(format!("{}; the target was restored", e));
+        }
+        Ok(result)
+    }
+
+    /// Apply the changes (skipping `failed_hunks`) and commit them with the original author
+    fn apply_and_commit(
+        &self,
+        commit: &Commit,
+        changes: &[FileChange],
+        failed_hunks: &[Vec<usize>],
+        workdir: &Path,
+        result: &mut PortedCommit,
+    ) -> Result<()> {
+        let any_failed = failed_hunks.iter().any(|f| !f.is_empty());
+        let mut index = self.target.index()?;
+        for (change, failed) in changes.iter().zip(failed_hunks) {
+            let whole_file_failed = !failed.is_empty()
+                && (change.hunk_headers.is_empty() || failed.len() == change.hunk_headers.len());
+            if !whole_file_failed {
+                match &change.patch {
+                    Some(patch) => {
+                        let diff = Diff::from_buffer(patch.as_bytes())?;
+                        let mut options = ApplyOptions::new();
+                        select_hunks(&mut options, |i| !failed.contains(&i));
+                        self.target
+                            .apply(&diff, ApplyLocation::Both, Some(&mut options))?;
+                    }
+                    None => {
+                        write_binary(workdir, change)?;
+                        let path = Path::new(&change.target_path);
+                        if change.deleted {
+                            index.remove_path(path)?;
+                        } else {
+                            index.add_path(path)?;
+                        }
+                        index.write()?;
+                    }
+                }
+            }
+            result
+                .files
+                .push(self.file_result(change, failed, whole_file_failed));
+        }
+
+        // 4. Commit with the original author
+        index.read(false)?;
+        let tree_id = index.write_tree()?;
+        let parent = self.target.head()?.peel_to_commit()?;
+        if parent.tree_id() == tree_id {
+            if any_failed {
+                result.message = Some("None of the changes apply to the target".to_string());
+            } else {
+                result.status = "empty".to_string();
+                result.message = Some("Nothing changed in the target".to_string());
+            }
+            return Ok(());
+        }
+
+        let author = commit.author();
+        let author = Signature::new(
+            author.name().unwrap_or("Unknown"),
+            author.email().unwrap_or(""),
+            &author.when(),
+        )?;
+        let mut provenance = self.provenance.clone();
+        provenance.source_commits = vec![commit.id().to_string()];
+        let message = provenance.append_to(commit.message().unwrap_or(""));
+        let tree = self.target.find_tree(tree_id)?;
+        let oid = self.target.commit(
+            Some("HEAD"),
+            &author,
+            &self.committer,
+            &message,
+            &tree,
+            &[&parent],
+        )?;
+
+        result.target_sha = Some(oid.to_string());
+        result.status = if any_failed { "partial" } else { "ported" }.to_string();
+        Ok(())
+    }
+
+    /// Force the touched paths back to HEAD in the index and working tree.
+    /// Files the port created are removed; files in `existing` were there before and stay.
+    fn restore(
+        &self,
+        changes: &[FileChange],
+        existing: &HashSet<String>,
+        workdir: &Path,
+    ) -> Result<()> {
+        let head = self.target.head()?.peel_to_tree()?;
+        let mut index = self.target.index()?;
+        index.read(true)?;
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force();
+        let mut any_tracked = false;
+        for change in changes {
+            let path = Path::new(&change.target_path);
+            if head.get_path(path).is_ok() {
+                checkout.path(&change.target_path);
+                any_tracked = true;
+            } else {
+                let _ = index.remove_path(path);
+                if !existing.contains(&change.target_path) {
+                    let _ = std::fs::remove_file(workdir.join(path));
+                }
+            }
+        }
+        index.write()?;
+        if any_tracked {
+            self.target.checkout_head(Some(&mut checkout))?;
+        }
+        Ok(())
+    }
+
+    /// The change at `index` with target paths, or the report entry for an unmapped file
+    fn rewrite(&self, diff: &Diff, index: usize) -> Result<Result<FileChange, PortedFile>> {
+        let delta = diff
+            .get_delta(index)
+            .ok_or_else(|| anyhow!("Missing diff delta"))?;
+        let deleted = delta.status() == Delta::Deleted;
+        let file = if deleted {
+            delta.old_file()
+        } else {
+            delta.new_file()
+        };
+        let source_path = file
+            .path()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+
+        let Some((_, target_path)) = DiffEngine::map_path(self.mappings, &source_path)? else {
+            return Ok(Err(PortedFile {
+                source_path,
+                target_path: None,
+                status: "unmapped".to_string(),
+                failed_hunks: Vec::new(),
+                message: Some("No mapping rule covers this path".to_string()),
+            }));
+        };
+
+        let patch = Patch::from_diff(diff, index)?;
+        let mut change = FileChange {
+            source_path,
+            target_path,
+            deleted,
+            patch: None,
+            blob: None,
+            hunk_headers: Vec::new(),
+        };
+        match patch {
+            Some(patch) if !delta.flags().is_binary() => {
+                for hunk in 0..patch.num_hunks() {
+                    let (header, _) = patch.hunk(hunk)?;
+                    change
+                        .hunk_headers
+                        .push(String::from_utf8_lossy(header.header()).trim().to_string());
+                }
+                change.patch = Some(render_patch(&patch, &change)?);
+            }
+            _ => {
+                if !deleted {
+                    change.blob = Some(self.source.find_blob(file.id())?.content().to_vec());
+                }
+            }
+        }
+        Ok(Ok(change))
+    }
+
+    /// Indexes of hunks that would not apply; every hunk when the file itself cannot be patched
+    fn failing_hunks(&self, change: &FileChange) -> Result<Vec<usize>> {
+        let Some(patch) = &change.patch else {
+            return Ok(Vec::new());
+        };
+        let diff = Diff::from_buffer(patch.as_bytes())?;
+        let mut check = ApplyOptions::new();
+        check.check(true);
+        if self
+            .target
+            .apply(&diff, ApplyLocation::Both, Some(&mut check))
+            .is_ok()
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut failed = Vec::new();
+        for hunk in 0..change.hunk_headers.len() {
+            let mut options = ApplyOptions::new();
+            options.check(true);
+            select_hunks(&mut options, |i| i == hunk);
+            if self
+                .target
+                .apply(&diff, ApplyLocation::Both, Some(&mut options))
+                .is_err()
+            {
+                failed.push(hunk);
+            }
+        }
+        // The file failed as a whole (e.g. it is missing in the target)
+        if failed.is_empty() || change.hunk_headers.is_empty() {
+            failed = (0..change.hunk_headers.len().max(1)).collect();
+        }
+        Ok(failed)
+    }
+
+    fn file_result(&self, change: &FileChange, failed: &[usize], skipped: bool) -> PortedFile {
+        let status = if failed.is_empty() {
+            "applied"
+        } else if skipped {
+            "failed"
+        } else if self.allow_partial {
+            "partial"
+        } else {
+            "failed"
+        };
+        PortedFile {
+            source_path: change.source_path.clone(),
+            target_path: Some(change.target_path.clone()),
+            status: status.to_string(),
+            failed_hunks: failed
+                .iter()
+                .filter_map(|i| change.hunk_headers.get(*i).cloned())
+                .collect(),
+            message: (skipped && change.hunk_headers.is_empty())
+                .then(|| "The file cannot be patched in the target".to_string()),
+        }
+    }
+}
+
+/// Select hunks by their index within the patch.
+/// The options keep a pointer to themselves once the callback is set, so they must not move after this.
+fn select_hunks<'a>(options: &mut ApplyOptions<'a>, mut keep: impl FnMut(usize) -> bool + 'a) {
+    let mut next = 0;
+    options.hunk_callback(move |_| {
+        let selected = keep(next);
+        next += 1;
+        selected
+    });
+}
+
+/// Unified diff of `patch` with both sides renamed to the target path
+fn render_patch(patch: &Patch, change: &FileChange) -> Result<String> {
+    let delta = patch.delta();
+    let path = &change.target_path;
+    let mode = |m: FileMode| match m {
+        FileMode::BlobExecutable => "100755",
+        _ => "100644",
+    };
+
+    let mut text = format!("diff --git a/{0} b/{0}\n", path);
+    match delta.status() {
+        Delta::Added => {
+            text.push_str(&format!(
+                "new file mode {}\n",
+                mode(delta.new_file().mode())
+            ));
+            text.push_str(&format!("--- /dev/null\n+++ b/{}\n", path));
+        }
+        Delta::Deleted => {
+            text.push_str(&format!(
+                "deleted file mode {}\n",
+                mode(delta.old_file().mode())
+            ));
+            text.push_str(&format!("--- a/{}\n+++ /dev/null\n", path));
+        }
+        _ => text.push_str(&format!("--- a/{0}\n+++ b/{0}\n", path)),
+    }
+
+    for hunk in 0..patch.num_hunks() {
+        let (header, lines) = patch.hunk(hunk)?;
+        text.push_str(&String::from_utf8_lossy(header.header()));
+        for line in 0..lines {
+            let line = patch.line_in_hunk(hunk, line)?;
+            match line.origin() {
+                origin @ (' ' | '+' | '-') => {
+                    text.push(origin);
+                    text.push_str(&String::from_utf8_lossy(line.content()));
+                    if !line.content().ends_with(b"\n") {
+                        text.push('\n');
+                    }
+                }
+                // End-of-file markers for content without a trailing newline
+                '=' | '>' | '<' => text.push_str("\\ No newline at end of file\n"),
+                _ => {}
+            }
+        }
+    }
+    Ok(text)
+}
+
+fn write_binary(workdir: &Path, change: &FileChange) -> Result<()> {
+    let full = workdir.join(&change.target_path);
+    if change.deleted {
+        if full.exists() {
+            std::fs::remove_file(&full)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&full, change.blob.as_deref().unwrap_or_default())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET: &str = "crate/src/lib.rs";
+
+    /// `render_patch` of `lib.rs` going from `old` to `new` (`None` for no file), ported to TARGET
+    fn rendered(old: Option<&str>, new: Option<&str>) -> String {
+        let dir = std::env::temp_dir().join(format!("render-patch-{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init_bare(&dir).unwrap();
+        let tree = |content: Option<&str>| {
+            let mut builder = repo.treebuilder(None).unwrap();
+            if let Some(content) = content {
+                let blob = repo.blob(content.as_bytes()).unwrap();
+                builder.insert("lib.rs", blob, 0o100644).unwrap();
+            }
+            repo.find_tree(builder.write().unwrap()).unwrap()
+        };
+        let diff = repo
+            .diff_tree_to_tree(Some(&tree(old)), Some(&tree(new)), None)
+            .unwrap();
+        let patch = Patch::from_diff(&diff, 0).unwrap().unwrap();
+        let change = FileChange {
+            source_path: "lib.rs".to_string(),
+            target_path: TARGET.to_string(),
+            deleted: new.is_none(),
+            patch: None,
+            blob: None,
+            hunk_headers: vec![],
+        };
+        let text = render_patch(&patch, &change).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        Diff::from_buffer(text.as_bytes()).expect("rendered patch should parse");
+        text
+    }
+
+    #[test]
+    fn modified_file_is_renamed_to_the_target_path() {
+        assert_eq!(
+            rendered(Some("a\nb\nc\n"), Some("a\nB\nc\n")),
+            "diff --git a/crate/src/lib.rs b/crate/src/lib.rs\n\
+             --- a/crate/src/lib.rs\n\
+             +++ b/crate/src/lib.rs\n\
+             @@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+        );
+    }
+
+    #[test]
+    fn added_and_deleted_files_use_dev_null() {
+        assert_eq!(
+            rendered(None, Some("a\n")),
+            "diff --git a/crate/src/lib.rs b/crate/src/lib.rs\n\
+             new file mode 100644\n\
+             --- /dev/null\n\
+             +++ b/crate/src/lib.rs\n\
+             @@ -0,0 +1 @@\n+a\n"
+        );
+        assert_eq!(
+            rendered(Some("a\n"), None),
+            "diff --git a/crate/src/lib.rs b/crate/src/lib.rs\n\
+             deleted file mode 100644\n\
+             --- a/crate/src/lib.rs\n\
+             +++ /dev/null\n\
+             @@ -1 +0,0 @@\n-a\n"
+        );
+    }
+
+    #[test]
+    fn missing_final_newline_is_marked() {
+        let text = rendered(Some("a\n"), Some("a\nb"));
+        assert!(
+            text.ends_with("+b\n\\ No newline at end of file\n"),
+            "{}",
+            text
+        );
+    }
+}
@@ -32,6 +32,11 @@ pub struct DiffSummary {
 pub struct DiffEngine;
 
 impl DiffEngine {
+    /// Hidden entries and build/dependency directories are never synced
+    pub fn is_excluded(name: &str) -> bool {
+        name.starts_with('.') || name == "node_modules" || name == "target" || name == "dist"
+    }
+
     #[allow(dead_code)]
     pub fn scan_changes(_route: &routes::Model, mappings_json: &str) -> Result<DiffSummary> {
         // 1. Parse mappings
@@ -66,23 +71,11 @@ impl DiffEngine {
             // Parse source and target globs
             let source_pattern = Pattern::new(&rule.source)?;
 
-            // Extract base prefix from glob (part before first * or **)
-            let source_base = Self::extract_glob_base(&rule.source);
-            let target_base = if rule.target.is_empty() {
-                // If target is empty, use same structure as source
-                source_base.clone()
-            } else {
-                Self::extract_glob_base(&rule.target)
-            };
-
             // Walk Source
-            for entry in WalkDir::new(source_root).into_iter().filter_entry(|e| {
-                let name = e.file_name().to_string_lossy();
-                !name.starts_with('.')
-                    && name != "node_modules"
-                    && name != "target"
-                    && name != "dist"
-            }) {
+            for entry in WalkDir::new(source_root)
+                .into_iter()
+                .filter_entry(|e| !Self::is_excluded(&e.file_name().to_string_lossy()))
+            {
                 let entry = entry?;
                 if entry.file_type().is_file() {
                     let relative_path = entry
@@ -102,14 +95,7 @@ impl DiffEngine {
 
                         let source_file = entry.path();
 
-                        // Calculate target path by replacing source_base with target_base
-                        let target_relative = if normalized_relative.starts_with(&source_base) {
-                            let suffix = &normalized_relative[source_base.len()..];
-                            format!("{}{}", target_base, suffix)
-                        } else {
-                            // If no base match, use same relative path with target_base prepended
-                            format!("{}{}", target_base, normalized_relative)
-                        };
+                        let target_relative = Self::rebase_path(rule, &normalized_relative);
 
                         let target_file = target_root.join(&target_relative);
 
@@ -161,6 +147,47 @@ impl DiffEngine {
         Ok(DiffSummary { changes })
     }
 
+    /// Map a source-relative path through `mappings` the way a sync does: excluded
+    /// paths map nowhere, ignore rules are skipped and the first matching copy rule wins.
+    /// Returns the index of that rule and the target-relative path.
+    pub fn map_path(
+        mappings: &[MappingRule],
+        relative_path: &str,
+    ) -> Result<Option<(usize, String)>> {
+        let normalized = relative_path.replace('\\', "/");
+        if normalized.split('/').any(Self::is_excluded) {
+            return Ok(None);
+        }
+        for (index, rule) in mappings.iter().enumerate() {
+            if rule.mode == "ignore" {
+                continue;
+            }
+            if Pattern::new(&rule.source)?.matches(&normalized) {
+                return Ok(Some((index, Self::rebase_path(rule, &normalized))));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Calculate the target path by replacing the rule's source base with its target base
+    fn rebase_path(rule: &MappingRule, normalized_relative: &str) -> String {
+        // Extract base prefix from glob (part before first * or **)
+        let source_base = Self::extract_glob_base(&rule.source);
+        let target_base = if rule.target.is_empty() {
+            // If target is empty, use same structure as source
+            source_base.clone()
+        } else {
+            Self::extract_glob_base(&rule.target)
+        };
+
+        if let Some(suffix) = normalized_relative.strip_prefix(&source_base) {
+            format!("{}{}", target_base, suffix)
+        } else {
+            // If no base match, use same relative path with target_base prepended
+            format!("{}{}", target_base, normalized_relative)
+        }
+    }
+
     /// Extract the base prefix from a glob pattern (part before first * or **)
     /// e.g., "src/**/*.vue" -> "src/"
     /// e.g., "**/*.ts" -> ""
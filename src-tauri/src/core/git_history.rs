@@ -0,0 +1,432 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+use git2::{Commit, Delta, DiffFindOptions, DiffOptions, Patch, Repository, Sort, Time};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+/// How far the time-sorted walk goes past `since` before stopping, for commits whose
+/// clock was behind their parent's
+const SINCE_SLACK_SECS: i64 = 24 * 60 * 60;
+/// Per-file cap on rendered patches so huge generated files do not flood the UI
+const MAX_PATCH_BYTES: usize = 256 * 1024;
+
+/// Filters for a history query; every field is optional
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct HistoryQuery {
+    /// Branch, tag or any revision to walk from (default HEAD)
+    #[serde(rename = "ref")]
+    pub reference: Option<String>,
+    /// Case-insensitive match on author name or email
+    pub author: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD` (start of day, UTC)
+    pub since: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD` (end of day, UTC)
+    pub until: Option<String>,
+    /// Only commits touching this file or directory
+    pub path: Option<String>,
+    /// Case-insensitive match on the commit message
+    pub message: Option<String>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    /// Compute per-file stats (default true)
+    pub include_stats: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ChangedFile {
+    pub path: String,
+    /// Previous path for renames and copies
+    pub old_path: Option<String>,
+    pub status: String, // "added" | "modified" | "deleted" | "renamed" | "copied" | "typechange"
+    pub insertions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CommitStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<ChangedFile>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HistoryCommit {
+    pub sha: String,
+    pub short_sha: String,
+    pub parents: Vec<String>,
+    pub author_name: String,
+    pub author_email: String,
+    pub authored_at: String,
+    pub committer_name: String,
+    pub committed_at: String,
+    pub summary: String,
+    pub message: String,
+    pub stats: Option<CommitStats>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileDiff {
+    #[serde(flatten)]
+    pub file: ChangedFile,
+    /// Unified diff of the file; empty for binary files
+    pub patch: String,
+    /// The patch was cut at `MAX_PATCH_BYTES`
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CommitDetail {
+    #[serde(flatten)]
+    pub commit: HistoryCommit,
+    pub files: Vec<FileDiff>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HistoryPage {
+    pub commits: Vec<HistoryCommit>,
+    /// Pass back as `cursor` to get the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+pub struct GitHistory;
+
+impl GitHistory {
+    /// One page of commits reachable from the query's ref, newest first
+    pub fn query(repo: &Repository, query: &HistoryQuery) -> Result<HistoryPage> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let since = query
+            .since
+            .as_deref()
+            .map(|s| parse_bound(s, false))
+            .transpose()?;
+        let until = query
+            .until
+            .as_deref()
+            .map(|s| parse_bound(s, true))
+            .transpose()?;
+        let author = lowercase_filter(&query.author);
+        let message = lowercase_filter(&query.message);
+        let path = query
+            .path
+            .as_deref()
+            .map(|p| p.trim().trim_matches('/').replace('\\', "/"))
+            .filter(|p| !p.is_empty());
+
+        let mut revwalk = repo.revwalk()?;
+        match query.reference.as_deref().filter(|r| !r.is_empty()) {
+            Some(reference) => {
+                let commit = repo
+                    .revparse_single(reference)
+                    .and_then(|obj| obj.peel_to_commit())
+                    .map_err(|e| anyhow!("Cannot resolve '{}': {}", reference, e))?;
+                revwalk.push(commit.id())?;
+            }
+            None => revwalk.push_head()?,
+        }
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+        // Skip everything up to and including the cursor commit
+        let mut waiting_for_cursor = query.cursor.as_deref().filter(|c| !c.is_empty());
+        let mut commits = Vec::new();
+        let mut has_more = false;
+
+        for oid in revwalk {
+            let oid = oid?;
+            if let Some(cursor) = waiting_for_cursor {
+                if oid.to_string() == cursor {
+                    waiting_for_cursor = None;
+                }
+                continue;
+            }
+
+            let commit = repo.find_commit(oid)?;
+            // The walk is sorted by commit time, so nothing newer than `since` follows
+            if since.is_some_and(|s| commit.time().seconds() < s - SINCE_SLACK_SECS) {
+                break;
+            }
+            let time = commit.author().when().seconds();
+            if since.is_some_and(|s| time < s) || until.is_some_and(|u| time > u) {
+                continue;
+            }
+            if let Some(author) = &author {
+                let signature = commit.author();
+                let matches = [signature.name(), signature.email()]
+                    .into_iter()
+                    .flatten()
+                    .any(|s| s.to_lowercase().contains(author));
+                if !matches {
+                    continue;
+                }
+            }
+            if let Some(message) = &message {
+                if !commit
+                    .message()
+                    .unwrap_or("")
+                    .to_lowercase()
+                    .contains(message)
+                {
+                    continue;
+                }
+            }
+            if let Some(path) = &path {
+                if !touches_path(repo, &commit, path)? {
+                    continue;
+                }
+            }
+
+            if commits.len() == limit {
+                has_more = true;
+                break;
+            }
+            let stats = if query.include_stats.unwrap_or(true) {
+                Some(Self::commit_stats(repo, &commit)?)
+            } else {
+                None
+            };
+            commits.push(to_history_commit(&commit, stats));
+        }
+
+        if waiting_for_cursor.is_some() {
+            return Err(anyhow!("Cursor not found in this history"));
+        }
+
+        let next_cursor = if has_more {
+            commits.last().map(|c| c.sha.clone())
+        } else {
+            None
+        };
+        Ok(HistoryPage {
+            commits,
+            next_cursor,
+        })
+    }
+
+    /// Files changed by `commit` against its first parent (the empty tree for root commits)
+    pub fn commit_stats(repo: &Repository, commit: &Commit) -> Result<CommitStats> {
+        let files: Vec<ChangedFile> = changed_files(repo, commit, None)?
+            .into_iter()
+            .map(|(file, _)| file)
+            .collect();
+        Ok(CommitStats {
+            files_changed: files.len(),
+            insertions: files.iter().map(|f| f.insertions).sum(),
+            deletions: files.iter().map(|f| f.deletions).sum(),
+            files,
+        })
+    }
+
+    /// Metadata, changed files and the unified diff of each file for one commit
+    pub fn commit_detail(
+        repo: &Repository,
+        revision: &str,
+        context_lines: Option<u32>,
+    ) -> Result<CommitDetail> {
+        let commit = repo
+            .revparse_single(revision)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|e| anyhow!("Cannot resolve '{}': {}", revision, e))?;
+
+        let files: Vec<FileDiff> = changed_files(repo, &commit, Some(context_lines.unwrap_or(3)))?
+            .into_iter()
+            .map(|(file, patch)| {
+                let mut patch = patch.unwrap_or_default();
+                let truncated = patch.len() > MAX_PATCH_BYTES;
+                if truncated {
+                    let mut cut = MAX_PATCH_BYTES;
+                    while !patch.is_char_boundary(cut) {
+                        cut -= 1;
+                    }
+                    patch.truncate(cut);
+                }
+                FileDiff {
+                    file,
+                    patch,
+                    truncated,
+                }
+            })
+            .collect();
+
+        let stats = CommitStats {
+            files_changed: files.len(),
+            insertions: files.iter().map(|f| f.file.insertions).sum(),
+            deletions: files.iter().map(|f| f.file.deletions).sum(),
+            files: files.iter().map(|f| f.file.clone()).collect(),
+        };
+        Ok(CommitDetail {
+            commit: to_history_commit(&commit, Some(stats)),
+            files,
+        })
+    }
+}
+
+impl CommitDetail {
+    /// Commit header followed by the full unified diff, as input for AI summaries
+    pub fn to_patch_text(&self) -> String {
+        let mut text = format!(
+            "commit {}\nAuthor: {} <{}>\nDate: {}\n\n{}\n\n",
+            self.commit.sha,
+            self.commit.author_name,
+            self.commit.author_email,
+            self.commit.authored_at,
+            self.commit.message
+        );
+        for file in &self.files {
+            text.push_str(&file.patch);
+            if file.truncated {
+                text.push_str("\n[diff truncated]\n");
+            }
+        }
+        text
+    }
+}
+
+/// Changed files of `commit` with rename detection, and their rendered patches when
+/// `patch_context` (lines of context) is given
+fn changed_files(
+    repo: &Repository,
+    commit: &Commit,
+    patch_context: Option<u32>,
+) -> Result<Vec<(ChangedFile, Option<String>)>> {
+    let mut options = DiffOptions::new();
+    if let Some(context) = patch_context {
+        options.context_lines(context);
+    }
+    let mut diff = diff_against_parent(repo, commit, Some(&mut options))?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true).copies(false)))?;
+
+    let mut files = Vec::new();
+    for index in 0..diff.deltas().len() {
+        let delta = diff
+            .get_delta(index)
+            .ok_or_else(|| anyhow!("Missing diff delta"))?;
+        let mut patch = Patch::from_diff(&diff, index)?;
+        let (insertions, deletions) = match &patch {
+            Some(patch) => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                (insertions, deletions)
+            }
+            None => (0, 0),
+        };
+        let rendered = match (&mut patch, patch_context) {
+            (Some(patch), Some(_)) => Some(String::from_utf8_lossy(&patch.to_buf()?).to_string()),
+            _ => None,
+        };
+
+        let new_path = delta
+            .new_file()
+            .path()
+            .map(|p| p.to_string_lossy().to_string());
+        let old_path = delta
+            .old_file()
+            .path()
+            .map(|p| p.to_string_lossy().to_string());
+        let path = new_path.or(old_path.clone()).unwrap_or_default();
+
+        files.push((
+            ChangedFile {
+                old_path: old_path.filter(|p| *p != path),
+                path,
+                status: delta_status(delta.status()).to_string(),
+                insertions,
+                deletions,
+                binary: delta.flags().is_binary(),
+            },
+            rendered,
+        ));
+    }
+    Ok(files)
+}
+
+/// Diff from the first parent's tree (or nothing) to the commit's tree
+pub(crate) fn diff_against_parent<'r>(
+    repo: &'r Repository,
+    commit: &Commit,
+    options: Option<&mut DiffOptions>,
+) -> Result<git2::Diff<'r>> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), options)?)
+}
+
+pub(crate) fn delta_status(delta: Delta) -> &'static str {
+    match delta {
+        Delta::Added => "added",
+        Delta::Deleted => "deleted",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        Delta::Typechange => "typechange",
+        _ => "modified",
+    }
+}
+
+/// ISO 8601 timestamp in the signature's own timezone
+pub(crate) fn format_git_time(time: Time) -> String {
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset"));
+    offset
+        .timestamp_opt(time.seconds(), 0)
+        .single()
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default()
+}
+
+pub(crate) fn to_history_commit(commit: &Commit, stats: Option<CommitStats>) -> HistoryCommit {
+    let sha = commit.id().to_string();
+    let author = commit.author();
+    let committer = commit.committer();
+    HistoryCommit {
+        short_sha: sha.chars().take(7).collect(),
+        sha,
+        parents: commit.parent_ids().map(|p| p.to_string()).collect(),
+        author_name: author.name().unwrap_or("Unknown").to_string(),
+        author_email: author.email().unwrap_or("").to_string(),
+        authored_at: format_git_time(author.when()),
+        committer_name: committer.name().unwrap_or("Unknown").to_string(),
+        committed_at: format_git_time(committer.when()),
+        summary: commit.summary().unwrap_or("").to_string(),
+        message: commit.message().unwrap_or("").trim().to_string(),
+        stats,
+    }
+}
+
+fn touches_path(repo: &Repository, commit: &Commit, path: &str) -> Result<bool> {
+    let mut options = DiffOptions::new();
+    options.pathspec(path);
+    Ok(diff_against_parent(repo, commit, Some(&mut options))?
+        .deltas()
+        .len()
+        > 0)
+}
+
+fn lowercase_filter(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty())
+}
+
+/// Unix seconds for an RFC 3339 timestamp or a plain date
+pub(crate) fn parse_bound(value: &str, end_of_day: bool) -> Result<i64> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow!("Invalid date '{}', expected YYYY-MM-DD or RFC 3339", value))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    }
+    .ok_or_else(|| anyhow!("Invalid date '{}'", value))?;
+    Ok(Utc.from_utc_datetime(&time).timestamp())
+}
@@ -0,0 +1,542 @@
+use crate::commands::git_branches::create_tracking_branch_in;
+use crate::core::credentials::CredentialResolver;
+use crate::database::entities::repositories;
+use anyhow::{anyhow, Result};
+use git2::build::CheckoutBuilder;
+use git2::{
+    AnnotatedCommit, BranchType, Direction, ErrorCode, FetchOptions, Oid, PushOptions, Remote,
+    Repository, ResetType, StatusOptions,
+};
+use serde::Serialize;
+use std::cell::RefCell;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RefUpdate {
+    pub name: String,
+    /// `None` when the ref was created
+    pub old_sha: Option<String>,
+    /// `None` when the ref was deleted
+    pub new_sha: Option<String>,
+}
+
+/// Structured outcome of a git operation
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GitOpResult {
+    pub operation: String,
+    pub branch: Option<String>,
+    pub old_sha: Option<String>,
+    pub new_sha: Option<String>,
+    pub refs_updated: Vec<RefUpdate>,
+    pub message: String,
+}
+
+impl GitOpResult {
+    /// Multi-line text for task logs
+    pub fn to_log(&self) -> String {
+        let mut log = format!("{}\n", self.message);
+        if let (Some(old), Some(new)) = (&self.old_sha, &self.new_sha) {
+            if old != new {
+                log.push_str(&format!("HEAD {} -> {}\n", short(old), short(new)));
+            }
+        }
+        for update in &self.refs_updated {
+            log.push_str(&format!(
+                "  {} {} -> {}\n",
+                update.name,
+                update.old_sha.as_deref().map(short).unwrap_or("(new)"),
+                update.new_sha.as_deref().map(short).unwrap_or("(deleted)")
+            ));
+        }
+        log
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PullMode {
+    FastForward,
+    Rebase,
+}
+
+/// Native fetch/pull/push/reset/checkout for a registered repository, authenticated with
+/// its stored credentials. Operations default to the repository's configured branch, then HEAD.
+pub struct GitRemote {
+    repo: Repository,
+    resolver: CredentialResolver,
+    default_branch: Option<String>,
+}
+
+impl GitRemote {
+    pub fn open(model: &repositories::Model) -> Result<Self> {
+        Ok(Self {
+            repo: Repository::open(&model.local_path)?,
+            resolver: CredentialResolver::for_repository(model)?,
+            default_branch: model.branch.clone().filter(|b| !b.is_empty()),
+        })
+    }
+
+    /// Run an operation by name, as used by task steps:
+    /// `fetch [remote]`, `pull [branch]`, `push [branch]`, `reset [revision]`, `reset_remote`,
+    /// `checkout <branch>`
+    pub fn run(
+        &self,
+        operation: &str,
+        arg: Option<&str>,
+        force_with_lease: bool,
+        rebase: bool,
+    ) -> Result<GitOpResult> {
+        let arg = arg.map(str::trim).filter(|a| !a.is_empty());
+        match operation {
+            "fetch" => self.fetch(arg),
+            "pull" => {
+                let mode = if rebase {
+                    PullMode::Rebase
+                } else {
+                    PullMode::FastForward
+                };
+                self.pull(arg, mode)
+            }
+            "push" => self.push(arg, force_with_lease),
+            "reset" => self.reset(arg),
+            "reset_remote" => self.reset_to_remote(),
+            "checkout" => self.checkout(arg.ok_or_else(|| anyhow!("checkout needs a branch"))?),
+            _ => Err(anyhow!("Unknown git operation: {}", operation)),
+        }
+    }
+
+    /// Fetch one remote, or every remote when `remote` is `None`
+    pub fn fetch(&self, remote: Option<&str>) -> Result<GitOpResult> {
+        let names: Vec<String> = match remote {
+            Some(name) => vec![name.to_string()],
+            None => self
+                .repo
+                .remotes()?
+                .iter()
+                .flatten()
+                .map(String::from)
+                .collect(),
+        };
+
+        let mut result = GitOpResult {
+            operation: "fetch".to_string(),
+            ..Default::default()
+        };
+        for name in &names {
+            result.refs_updated.extend(self.fetch_remote(name)?);
+        }
+        result.message = format!(
+            "Fetched {} remote(s), {} ref(s) updated",
+            names.len(),
+            result.refs_updated.len()
+        );
+        Ok(result)
+    }
+
+    pub fn pull(&self, branch: Option<&str>, mode: PullMode) -> Result<GitOpResult> {
+        let branch = self.target_branch(branch)?;
+        let (remote, remote_branch) = self.upstream_of(&branch);
+        let refs_updated = self.fetch_remote(&remote)?;
+
+        let local_ref = format!("refs/heads/{}", branch);
+        let tracking_ref = format!("refs/remotes/{}/{}", remote, remote_branch);
+        let upstream_oid = self
+            .repo
+            .refname_to_id(&tracking_ref)
+            .map_err(|_| anyhow!("Remote branch {}/{} not found", remote, remote_branch))?;
+        let old_oid = self.repo.refname_to_id(&local_ref).ok();
+        let is_head = self.head_branch()?.as_deref() == Some(branch.as_str());
+
+        let mut result = GitOpResult {
+            operation: "pull".to_string(),
+            branch: Some(branch.clone()),
+            old_sha: old_oid.map(|o| o.to_string()),
+            refs_updated,
+            ..Default::default()
+        };
+
+        let Some(old_oid) = old_oid else {
+            // No local branch yet: create it at the upstream commit
+            let commit = self.repo.find_commit(upstream_oid)?;
+            let mut created = self.repo.branch(&branch, &commit, false)?;
+            created.set_upstream(Some(&format!("{}/{}", remote, remote_branch)))?;
+            result.new_sha = Some(upstream_oid.to_string());
+            result.message = format!("Created {} from {}/{}", branch, remote, remote_branch);
+            return Ok(result);
+        };
+
+        let upstream = self.repo.find_annotated_commit(upstream_oid)?;
+        let (analysis, _) = self
+            .repo
+            .merge_analysis_for_ref(&self.repo.find_reference(&local_ref)?, &[&upstream])?;
+
+        result.message = if analysis.is_up_to_date() {
+            "Already up to date".to_string()
+        } else if analysis.is_fast_forward() {
+            self.fast_forward(&local_ref, upstream_oid, is_head)?;
+            format!(
+                "Fast-forwarded {} to {}",
+                branch,
+                short(&upstream_oid.to_string())
+            )
+        } else if mode == PullMode::Rebase {
+            if !is_head {
+                return Err(anyhow!("Check out {} before rebasing it", branch));
+            }
+            self.rebase_onto(&upstream)?;
+            format!("Rebased {} onto {}/{}", branch, remote, remote_branch)
+        } else {
+            return Err(anyhow!(
+                "{} and {}/{} have diverged; pull with rebase instead",
+                branch,
+                remote,
+                remote_branch
+            ));
+        };
+
+        result.new_sha = self
+            .repo
+            .refname_to_id(&local_ref)
+            .ok()
+            .map(|o| o.to_string())
+            .or(Some(old_oid.to_string()));
+        Ok(result)
+    }
+
+    /// Push a branch to its upstream. With `force_with_lease` the push overwrites the remote
+    /// branch, but only if it still points where our remote-tracking ref says it does.
+    pub fn push(&self, branch: Option<&str>, force_with_lease: bool) -> Result<GitOpResult> {
+        let branch = self.target_branch(branch)?;
+        let (remote_name, remote_branch) = self.upstream_of(&branch);
+        let local_ref = format!("refs/heads/{}", branch);
+        let local_oid = self.repo.refname_to_id(&local_ref)?;
+        let remote_ref = format!("refs/heads/{}", remote_branch);
+        let tracking_ref = format!("refs/remotes/{}/{}", remote_name, remote_branch);
+        let expected = self.repo.refname_to_id(&tracking_ref).ok();
+        let mut remote = self.repo.find_remote(&remote_name)?;
+
+        if force_with_lease {
+            let actual = self.remote_ref_oid(&mut remote, &remote_ref)?;
+            if actual != expected {
+                return Err(anyhow!(
+                    "{}/{} changed since the last fetch; fetch and review before force pushing",
+                    remote_name,
+                    remote_branch
+                ));
+            }
+        }
+
+        let refspec = format!(
+            "{}{}:{}",
+            if force_with_lease { "+" } else { "" },
+            local_ref,
+            remote_ref
+        );
+        self.push_refspecs(&mut remote, &[refspec])?;
+
+        Ok(GitOpResult {
+            operation: "push".to_string(),
+            branch: Some(branch.clone()),
+            old_sha: expected.map(|o| o.to_string()),
+            new_sha: Some(local_oid.to_string()),
+            refs_updated: vec![RefUpdate {
+                name: format!("{}/{}", remote_name, remote_branch),
+                old_sha: expected.map(|o| o.to_string()),
+                new_sha: Some(local_oid.to_string()),
+            }],
+            message: format!("Pushed {} to {}/{}", branch, remote_name, remote_branch),
+        })
+    }
+
+    /// Push tags (all local tags when `tags` is empty), or delete them on the remote with `delete`
+    pub fn push_tags(
+        &self,
+        remote: Option<&str>,
+        tags: &[String],
+        delete: bool,
+    ) -> Result<GitOpResult> {
+        let remote_name = match remote {
+            Some(name) => name.to_string(),
+            None => self.default_remote()?,
+        };
+        let mut remote = self.repo.find_remote(&remote_name)?;
+
+        let names: Vec<String> = if tags.is_empty() {
+            if delete {
+                return Err(anyhow!("Name the tags to delete"));
+            }
+            // libgit2 cannot push wildcard refspecs, so list the tags
+            self.repo
+                .tag_names(None)?
+                .iter()
+                .flatten()
+                .map(String::from)
+                .collect()
+        } else {
+            tags.to_vec()
+        };
+        let refspecs: Vec<String> = names
+            .iter()
+            .map(|tag| {
+                if delete {
+                    format!(":refs/tags/{}", tag)
+                } else {
+                    format!("refs/tags/{0}:refs/tags/{0}", tag)
+                }
+            })
+            .collect();
+        if refspecs.is_empty() {
+            return Err(anyhow!("Repository has no tags"));
+        }
+        self.push_refspecs(&mut remote, &refspecs)?;
+
+        let what = if tags.is_empty() {
+            "all tags".to_string()
+        } else {
+            tags.join(", ")
+        };
+        Ok(GitOpResult {
+            operation: if delete { "delete_tags" } else { "push_tags" }.to_string(),
+            message: if delete {
+                format!("Deleted {} on {}", what, remote_name)
+            } else {
+                format!("Pushed {} to {}", what, remote_name)
+            },
+            ..Default::default()
+        })
+    }
+
+    /// Hard reset HEAD to `target` (any revision, default `HEAD`)
+    pub fn reset(&self, target: Option<&str>) -> Result<GitOpResult> {
+        let old = self.head_oid();
+        let target = target.unwrap_or("HEAD");
+        let object = self.repo.revparse_single(target)?;
+        self.repo.reset(&object, ResetType::Hard, None)?;
+
+        Ok(GitOpResult {
+            operation: "reset".to_string(),
+            branch: self.head_branch()?,
+            old_sha: old,
+            new_sha: self.head_oid(),
+            refs_updated: vec![],
+            message: format!("Reset to {}", target),
+        })
+    }
+
+    /// Fetch, then hard reset the current branch to its upstream
+    pub fn reset_to_remote(&self) -> Result<GitOpResult> {
+        let branch = self
+            .head_branch()?
+            .ok_or_else(|| anyhow!("Repository is in detached HEAD state"))?;
+        let (remote, remote_branch) = self.upstream_of(&branch);
+        let refs_updated = self.fetch_remote(&remote)?;
+
+        let mut result = self.reset(Some(&format!("refs/remotes/{}/{}", remote, remote_branch)))?;
+        result.operation = "reset_remote".to_string();
+        result.refs_updated = refs_updated;
+        result.message = format!("Reset {} to {}/{}", branch, remote, remote_branch);
+        Ok(result)
+    }
+
+    /// Check out a local branch; when it does not exist a branch tracking `<remote>/<branch>`
+    /// of the default remote is created.
+    /// Local changes that would be overwritten abort the checkout.
+    pub fn checkout(&self, branch: &str) -> Result<GitOpResult> {
+        let old = self.head_oid();
+        let local = match self.repo.find_branch(branch, BranchType::Local) {
+            Ok(local) => local,
+            Err(_) => {
+                let remote_branch = format!("{}/{}", self.default_remote()?, branch);
+                create_tracking_branch_in(&self.repo, &remote_branch, Some(branch))
+                    .map_err(|e| anyhow!(e))?;
+                self.repo.find_branch(branch, BranchType::Local)?
+            }
+        };
+
+        let commit = local.get().peel_to_commit()?;
+        self.repo
+            .checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        self.repo.set_head(&format!("refs/heads/{}", branch))?;
+
+        Ok(GitOpResult {
+            operation: "checkout".to_string(),
+            branch: Some(branch.to_string()),
+            old_sha: old,
+            new_sha: Some(commit.id().to_string()),
+            refs_updated: vec![],
+            message: format!("Checked out {}", branch),
+        })
+    }
+
+    fn fetch_remote(&self, name: &str) -> Result<Vec<RefUpdate>> {
+        let updates = RefCell::new(Vec::new());
+        let mut remote = self.repo.find_remote(name)?;
+        let mut callbacks = self.resolver.remote_callbacks();
+        callbacks.update_tips(|refname, old, new| {
+            updates.borrow_mut().push(RefUpdate {
+                name: refname.to_string(),
+                old_sha: oid_string(old),
+                new_sha: oid_string(new),
+            });
+            true
+        });
+
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        // Empty refspecs mean the remote's configured fetch refspecs
+        remote.fetch::<&str>(&[], Some(&mut options), None)?;
+        drop(options);
+
+        Ok(updates.into_inner())
+    }
+
+    fn fast_forward(&self, local_ref: &str, target: Oid, is_head: bool) -> Result<()> {
+        if is_head {
+            // Update the working tree first so conflicting local changes abort the pull
+            let commit = self.repo.find_commit(target)?;
+            self.repo
+                .checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        }
+        self.repo
+            .find_reference(local_ref)?
+            .set_target(target, "pull: fast-forward")?;
+        Ok(())
+    }
+
+    fn rebase_onto(&self, upstream: &AnnotatedCommit) -> Result<()> {
+        self.ensure_clean()?;
+        let signature = self.repo.signature()?;
+        let mut rebase = self.repo.rebase(None, Some(upstream), None, None)?;
+
+        while let Some(operation) = rebase.next() {
+            operation?;
+            if self.repo.index()?.has_conflicts() {
+                rebase.abort()?;
+                return Err(anyhow!("Rebase stopped on conflicts and was aborted"));
+            }
+            if let Err(e) = rebase.commit(None, &signature, None) {
+                // The patch is already upstream; nothing to commit for it
+                if e.code() != ErrorCode::Applied {
+                    rebase.abort()?;
+                    return Err(e.into());
+                }
+            }
+        }
+        rebase.finish(Some(&signature))?;
+        Ok(())
+    }
+
+    fn ensure_clean(&self) -> Result<()> {
+        let mut options = StatusOptions::new();
+        options.include_untracked(false).include_ignored(false);
+        if !self.repo.statuses(Some(&mut options))?.is_empty() {
+            return Err(anyhow!("Working tree has uncommitted changes"));
+        }
+        Ok(())
+    }
+
+    /// Push refspecs, failing when the remote rejects any of them
+    fn push_refspecs(&self, remote: &mut Remote, refspecs: &[String]) -> Result<()> {
+        let rejection: RefCell<Option<String>> = RefCell::new(None);
+        let mut callbacks = self.resolver.remote_callbacks();
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(message) = status {
+                *rejection.borrow_mut() = Some(format!("{} rejected: {}", refname, message));
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+
+        let refspecs: Vec<&str> = refspecs.iter().map(String::as_str).collect();
+        remote.push(&refspecs, Some(&mut options))?;
+        drop(options);
+        match rejection.into_inner() {
+            Some(message) => Err(anyhow!(message)),
+            None => Ok(()),
+        }
+    }
+
+    /// `origin` when configured, otherwise the first remote
+    fn default_remote(&self) -> Result<String> {
+        let remotes = self.repo.remotes()?;
+        let names: Vec<&str> = remotes.iter().flatten().collect();
+        names
+            .iter()
+            .find(|n| **n == "origin")
+            .or(names.first())
+            .map(|n| n.to_string())
+            .ok_or_else(|| anyhow!("Repository has no remotes"))
+    }
+
+    fn remote_ref_oid(&self, remote: &mut Remote, refname: &str) -> Result<Option<Oid>> {
+        let connection = remote.connect_auth(
+            Direction::Push,
+            Some(self.resolver.remote_callbacks()),
+            None,
+        )?;
+        let oid = connection
+            .list()?
+            .iter()
+            .find(|head| head.name() == refname)
+            .map(|head| head.oid());
+        Ok(oid)
+    }
+
+    /// The explicit branch, the repository's configured branch, or the checked out one
+    fn target_branch(&self, explicit: Option<&str>) -> Result<String> {
+        if let Some(branch) = explicit.or(self.default_branch.as_deref()) {
+            return Ok(branch.to_string());
+        }
+        self.head_branch()?
+            .ok_or_else(|| anyhow!("Repository is in detached HEAD state"))
+    }
+
+    fn head_branch(&self) -> Result<Option<String>> {
+        let head = self.repo.head()?;
+        Ok(if head.is_branch() {
+            head.shorthand().map(String::from)
+        } else {
+            None
+        })
+    }
+
+    fn head_oid(&self) -> Option<String> {
+        self.repo
+            .head()
+            .ok()
+            .and_then(|h| h.target())
+            .map(|o| o.to_string())
+    }
+
+    /// (remote, branch on the remote) that `branch` tracks; `origin/<branch>` when unset
+    fn upstream_of(&self, branch: &str) -> (String, String) {
+        let refname = format!("refs/heads/{}", branch);
+        let remote = self
+            .repo
+            .branch_upstream_remote(&refname)
+            .ok()
+            .and_then(|buf| buf.as_str().map(String::from))
+            .unwrap_or_else(|| "origin".to_string());
+        let merge = self
+            .repo
+            .branch_upstream_merge(&refname)
+            .ok()
+            .and_then(|buf| {
+                buf.as_str()
+                    .map(|s| s.trim_start_matches("refs/heads/").to_string())
+            })
+            .unwrap_or_else(|| branch.to_string());
+        (remote, merge)
+    }
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(8)]
+}
+
+fn oid_string(oid: Oid) -> Option<String> {
+    if oid.is_zero() {
+        None
+    } else {
+        Some(oid.to_string())
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::HashSet;
+
+/// Ids of `root_id` and every group nested below it, from `(id, parent id)` pairs.
+/// Works for both repository and route groups.
+pub fn descendant_group_ids<'a>(
+    groups: impl Iterator<Item = (&'a str, Option<&'a str>)> + Clone,
+    root_id: &str,
+) -> HashSet<String> {
+    let mut ids = HashSet::new();
+    ids.insert(root_id.to_string());
+
+    // Expand level by level until no new children are found
+    loop {
+        let before = ids.len();
+        for (id, parent) in groups.clone() {
+            if parent.is_some_and(|p| ids.contains(p)) {
+                ids.insert(id.to_string());
+            }
+        }
+        if ids.len() == before {
+            break;
+        }
+    }
+    ids
+}
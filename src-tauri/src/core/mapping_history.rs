@@ -0,0 +1,129 @@
+use crate::commands::route::MappingRule;
+use crate::database::entities::route_mapping_revisions;
+use anyhow::Result;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RuleChange {
+    pub before: MappingRule,
+    pub after: MappingRule,
+}
+
+/// Structural difference between two mapping rule lists.
+/// Rules are matched by their `source` pattern: a rule whose source exists on both
+/// sides but whose target or mode differs is reported as changed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MappingDiff {
+    pub added: Vec<MappingRule>,
+    pub removed: Vec<MappingRule>,
+    pub changed: Vec<RuleChange>,
+    pub unchanged: usize,
+    /// Rules kept on both sides appear in a different order (order decides which rule wins)
+    pub reordered: bool,
+}
+
+impl MappingDiff {
+    pub fn compute(old: &[MappingRule], new: &[MappingRule]) -> Self {
+        let mut diff = MappingDiff::default();
+        let mut old_left: Vec<Option<&MappingRule>> = old.iter().map(Some).collect();
+        let mut new_left: Vec<Option<&MappingRule>> = new.iter().map(Some).collect();
+
+        // Pair up identical rules first, then rules sharing a source pattern
+        let mut kept_order_old = Vec::new();
+        let mut kept_order_new = Vec::new();
+        for (new_index, slot) in new_left.iter_mut().enumerate() {
+            let rule = slot.expect("slot is filled");
+            if let Some(old_index) = old_left.iter().position(|o| o == &Some(rule)) {
+                old_left[old_index] = None;
+                *slot = None;
+                diff.unchanged += 1;
+                kept_order_old.push(old_index);
+                kept_order_new.push(new_index);
+            }
+        }
+        for (new_index, slot) in new_left.iter_mut().enumerate() {
+            let Some(rule) = *slot else { continue };
+            if let Some(old_index) = old_left
+                .iter()
+                .position(|o| o.is_some_and(|o| o.source == rule.source))
+            {
+                let before = old_left[old_index].take().expect("slot is filled");
+                *slot = None;
+                diff.changed.push(RuleChange {
+                    before: before.clone(),
+                    after: rule.clone(),
+                });
+                kept_order_old.push(old_index);
+                kept_order_new.push(new_index);
+            }
+        }
+
+        diff.removed = old_left.into_iter().flatten().cloned().collect();
+        diff.added = new_left.into_iter().flatten().cloned().collect();
+
+        // Sort the kept pairs by new position and check the old positions stay ascending
+        let mut pairs: Vec<(usize, usize)> =
+            kept_order_new.into_iter().zip(kept_order_old).collect();
+        pairs.sort();
+        diff.reordered = pairs.windows(2).any(|w| w[0].1 > w[1].1);
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && !self.reordered
+    }
+}
+
+/// Parse a stored mappings JSON string; missing or blank values mean "no rules"
+pub fn parse_mappings(json: Option<&str>) -> Result<Vec<MappingRule>> {
+    match json {
+        Some(s) if !s.trim().is_empty() => Ok(serde_json::from_str(s)?),
+        _ => Ok(vec![]),
+    }
+}
+
+pub struct MappingHistory;
+
+impl MappingHistory {
+    /// Store a revision for a mapping change. Returns `None` when nothing actually changed.
+    pub async fn record<C: ConnectionTrait>(
+        db: &C,
+        route_id: &str,
+        previous: Option<String>,
+        new: Option<String>,
+        note: Option<String>,
+    ) -> Result<Option<route_mapping_revisions::Model>> {
+        // Unparsable (legacy) JSON must not block saving; such revisions just carry no diff
+        let diff = match (
+            parse_mappings(previous.as_deref()),
+            parse_mappings(new.as_deref()),
+        ) {
+            (Ok(old_rules), Ok(new_rules)) => {
+                let diff = MappingDiff::compute(&old_rules, &new_rules);
+                if diff.is_empty() {
+                    return Ok(None);
+                }
+                Some(serde_json::to_string(&diff)?)
+            }
+            _ if previous == new => return Ok(None),
+            _ => None,
+        };
+
+        let active = route_mapping_revisions::ActiveModel {
+            route_id: Set(route_id.to_string()),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            previous_mappings: Set(previous),
+            new_mappings: Set(new),
+            diff: Set(diff),
+            note: Set(note),
+            ..Default::default()
+        };
+
+        Ok(Some(active.insert(db).await?))
+    }
+}
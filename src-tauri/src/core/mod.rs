@@ -1,8 +1,30 @@
 pub mod ai_service;
+pub mod background_fetch;
+pub mod commit_port;
+pub mod credentials;
 pub mod dependency_scanner;
 pub mod diff;
+pub mod git_history;
+pub mod git_remote;
+pub mod groups;
+pub mod mapping_history;
 pub mod orchestrator;
+pub mod periodic;
+pub mod provenance;
+pub mod reconcile;
+pub mod relocate;
+pub mod repo_health;
+pub mod repo_impact;
+pub mod repo_routes;
+pub mod route_bundle;
+pub mod route_graph;
+pub mod route_impact;
+pub mod route_template;
 pub mod scheduler;
+pub mod status_history;
+pub mod status_watcher;
 pub mod sync;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub mod system;
@@ -34,15 +34,21 @@ pub struct AIParams {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GitParams {
     pub repo_id: String,
-    pub operation: String, // "pull", "push", "fetch", "reset"
+    pub operation: String, // "pull", "push", "fetch", "reset", "reset_remote", "checkout"
     pub arg: Option<String>,
     #[serde(default)]
-    pub force_push: bool, // Only used for push operation
+    pub force_push: bool, // Push only: force-with-lease against the last fetched remote ref
+    #[serde(default)]
+    pub rebase: bool, // Pull only: rebase when the branches have diverged
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SyncParams {
     pub route_id: String,
+    #[serde(default)]
+    pub commit: bool, // Commit the synced files in the target with provenance trailers
+    #[serde(default)]
+    pub commit_message: Option<String>,
 }
 
 pub trait StepExecutor {
@@ -71,134 +77,82 @@ impl StepExecutor for ScriptExecutor {
     }
 }
 
-// Placeholder for Git and Sync executors
-pub struct GitExecutor;
-impl StepExecutor for GitExecutor {
-    fn execute(&self, _params: &str) -> Result<String> {
-        // TODO: Implement Git logic using git2 or command
-        Ok("Git operation executed (Mock)".to_string())
-    }
-}
-
+use crate::commands::git_changes::{commit_signature, configured_identity};
 use crate::core::ai_service::AIService;
-use crate::core::sync::SyncEngine;
+use crate::core::credentials::redact;
+use crate::core::git_remote::GitRemote;
+use crate::core::sync::{SyncEngine, SyncResult};
 use crate::database::entities::repositories;
 use crate::database::manager::DatabaseManager;
 
-/// Helper to execute a single shell command in a directory
-fn run_command(args: Vec<&str>, dir: &str) -> Result<(String, String, bool)> {
-    let output = Command::new("git").args(&args).current_dir(dir).output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    Ok((stdout, stderr, output.status.success()))
-}
-
-/// Reset the local repository to match its remote counterpart exactly
-async fn execute_reset_to_remote(repo_name: &str, repo_path: &str) -> Result<String> {
-    let mut logs = format!(
-        "[Git RESET_REMOTE] Repository: {} ({})\n",
-        repo_name, repo_path
-    );
-
-    // 1. Fetch latest from remote
-    logs.push_str("> git fetch --all\n");
-    let (stdout, stderr, success) = run_command(vec!["fetch", "--all"], repo_path)?;
-    logs.push_str(&stdout);
-    logs.push_str(&stderr);
-    if !success {
-        return Err(anyhow::anyhow!("git fetch failed:\n{}", logs));
-    }
-
-    // 2. Get current branch name
-    let (stdout, _, success) = run_command(vec!["rev-parse", "--abbrev-ref", "HEAD"], repo_path)?;
-    if !success {
-        return Err(anyhow::anyhow!("Failed to get current branch name"));
-    }
-    let branch = stdout.trim();
-    if branch.is_empty() || branch == "HEAD" {
-        return Err(anyhow::anyhow!("Repository is in detached HEAD state"));
-    }
-
-    // 3. Reset to remote counterpart (assuming origin/branch)
-    let remote_target = format!("origin/{}", branch);
-    logs.push_str(&format!("> git reset --hard {}\n", remote_target));
-    let (stdout, stderr, success) =
-        run_command(vec!["reset", "--hard", &remote_target], repo_path)?;
-    logs.push_str(&stdout);
-    logs.push_str(&stderr);
-
-    if !success {
-        return Err(anyhow::anyhow!("git reset --hard failed:\n{}", logs));
-    }
-
-    Ok(logs)
-}
-
-/// Execute a real Git operation (pull, push, fetch, reset) on the specified repository
+/// Execute a Git operation (fetch, pull, push, reset, reset_remote, checkout) natively via libgit2
 async fn execute_git_operation(params: &GitParams, db_manager: &DatabaseManager) -> Result<String> {
     let db = &db_manager.connection;
 
-    // 1. Get repository info from database
     let repo = repositories::Entity::find_by_id(&params.repo_id)
         .one(db)
         .await?
         .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", params.repo_id))?;
 
-    let repo_path = &repo.local_path;
-
-    // 2. Handle special operations that require multiple commands
-    if params.operation == "reset_remote" {
-        return execute_reset_to_remote(&repo.name, repo_path).await;
-    }
-
-    // 3. Build command based on operation
-    let (cmd, git_args): (&str, Vec<&str>) = match params.operation.as_str() {
-        "pull" => ("gemini", vec!["/pull", "-y"]),
-        "push" => ("gemini", vec!["/commit", "-y"]),
-        "fetch" => ("git", vec!["fetch", "--all"]),
-        "reset" => ("git", vec!["reset", "--hard", "HEAD"]),
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Unknown git operation: {}",
-                params.operation
-            ))
-        }
-    };
-
-    println!(
-        "[execute_git_operation] Running: {} {:?} in {}",
-        cmd, git_args, repo_path
-    );
-
-    // 4. Execute command
-    let output = Command::new(cmd)
-        .args(&git_args)
-        .current_dir(repo_path)
-        .output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    let result = format!(
-        "[Git {}] Repository: {} ({})\n{}{}",
+    let header = format!(
+        "[Git {}] Repository: {} ({})\n",
         params.operation.to_uppercase(),
         repo.name,
-        repo_path,
-        stdout,
-        stderr
+        repo.local_path
+    );
+    println!(
+        "[execute_git_operation] Running {} in {}",
+        params.operation, repo.local_path
     );
 
-    if !output.status.success() {
-        return Err(anyhow::anyhow!(
-            "Git {} failed:\n{}",
+    let operation = params.operation.clone();
+    let arg = params.arg.clone();
+    let (force_push, rebase) = (params.force_push, params.rebase);
+    let result = tokio::task::spawn_blocking(move || {
+        GitRemote::open(&repo)?.run(&operation, arg.as_deref(), force_push, rebase)
+    })
+    .await?
+    .map_err(|e| {
+        anyhow::anyhow!(
+            "Git {} failed:\n{}{}",
             params.operation,
-            result
+            header,
+            redact(&e.to_string())
+        )
+    })?;
+
+    let log = format!("{}{}", header, result.to_log());
+    println!("[execute_git_operation] Success: {}", redact(log.trim()));
+    Ok(log)
+}
+
+/// Commit a finished sync in the target; a sync with failed copies is not committed
+async fn commit_sync(
+    result: SyncResult,
+    message: Option<String>,
+    db_manager: &DatabaseManager,
+) -> Result<String> {
+    if result.fail_count > 0 {
+        return Err(anyhow::anyhow!(
+            "{}Not committing: {} file(s) failed to sync",
+            result.logs,
+            result.fail_count
         ));
     }
-
-    println!("[execute_git_operation] Success: {}", result.trim());
-    Ok(result)
+    let identity = configured_identity(db_manager)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    tokio::task::spawn_blocking(move || {
+        let repo = git2::Repository::open(&result.target_path)?;
+        let signature = commit_signature(&repo, &identity).map_err(|e| anyhow::anyhow!(e))?;
+        let line = match SyncEngine::commit(&result, message.as_deref(), &signature)? {
+            Some(sha) => format!("[COMMIT] {}\n", sha),
+            None => "[COMMIT] Nothing to commit\n".to_string(),
+        };
+        Ok(format!("{}{}", result.logs, line))
+    })
+    .await?
 }
 
 #[allow(dead_code)]
@@ -279,6 +233,7 @@ impl TaskRunner {
                     // Sync needs async execution and DB access
                     let p: SyncParams = serde_json::from_str(&params)?;
                     match SyncEngine::execute_sync(&p.route_id, db_manager).await {
+                        Ok(res) if p.commit => commit_sync(res, p.commit_message, db_manager).await,
                         Ok(res) => Ok(res.logs),
                         Err(e) => Err(e),
                     }
@@ -297,10 +252,16 @@ impl TaskRunner {
 
             match result {
                 Ok(output) => {
-                    full_log.push_str(&format!("Step {} Success:\n{}\n", step.step_order, output));
+                    full_log.push_str(&redact(&format!(
+                        "Step {} Success:\n{}\n",
+                        step.step_order, output
+                    )));
                 }
                 Err(e) => {
-                    full_log.push_str(&format!("Step {} Failed:\n{}\n", step.step_order, e));
+                    full_log.push_str(&redact(&format!(
+                        "Step {} Failed:\n{}\n",
+                        step.step_order, e
+                    )));
                     success = false;
                     break;
                 }
@@ -0,0 +1,72 @@
+use crate::database::entities::settings;
+use anyhow::Result;
+use sea_orm::{ConnectionTrait, EntityTrait};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+/// How often a periodic job is woken up; jobs decide for themselves whether work is due
+const TICK: Duration = Duration::from_secs(60);
+
+/// Background loop shared by the fetcher and the status recorder
+pub struct PeriodicTask {
+    name: &'static str,
+    running: Arc<RwLock<bool>>,
+}
+
+impl PeriodicTask {
+    /// `name` tags the log lines, e.g. "BackgroundFetch"
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            running: Arc::new(RwLock::new(false)),
+        }
+    }
+
+    /// Run `job` every minute after `initial_delay` until stopped; errors are logged
+    pub async fn start<F, Fut>(&self, initial_delay: Duration, job: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send,
+    {
+        let mut running = self.running.write().await;
+        if *running {
+            return;
+        }
+        *running = true;
+        drop(running);
+
+        let name = self.name;
+        let running_flag = self.running.clone();
+
+        tokio::spawn(async move {
+            sleep(initial_delay).await;
+
+            loop {
+                if !*running_flag.read().await {
+                    println!("[{}] Stopping", name);
+                    break;
+                }
+
+                if let Err(e) = job().await {
+                    eprintln!("[{}] Error: {}", name, e);
+                }
+
+                sleep(TICK).await;
+            }
+        });
+    }
+
+    pub async fn stop(&self) {
+        *self.running.write().await = false;
+    }
+}
+
+/// Integer setting, `None` when unset or not a number
+pub async fn int_setting<C: ConnectionTrait>(db: &C, key: &str) -> Result<Option<i64>> {
+    Ok(settings::Entity::find_by_id(key)
+        .one(db)
+        .await?
+        .and_then(|s| s.value.trim().parse().ok()))
+}
@@ -0,0 +1,86 @@
+/// Trailer naming the route a target commit came through
+pub const ROUTE_TRAILER: &str = "Source-Bridge-Route";
+/// Trailer naming the source repository
+pub const SOURCE_REPO_TRAILER: &str = "Source-Repository";
+/// Trailer naming a source commit; repeated when several commits are combined
+pub const SOURCE_COMMIT_TRAILER: &str = "Source-Commit";
+
+/// Where a commit in a target repository came from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Provenance {
+    pub route_id: String,
+    pub source_repo_id: Option<String>,
+    pub source_commits: Vec<String>,
+}
+
+impl Provenance {
+    /// Git trailer lines, one per line
+    pub fn trailers(&self) -> String {
+        let mut lines = vec![format!("{}: {}", ROUTE_TRAILER, self.route_id)];
+        if let Some(repo_id) = &self.source_repo_id {
+            lines.push(format!("{}: {}", SOURCE_REPO_TRAILER, repo_id));
+        }
+        for sha in &self.source_commits {
+            lines.push(format!("{}: {}", SOURCE_COMMIT_TRAILER, sha));
+        }
+        lines.join("\n")
+    }
+
+    /// `message` with the trailers appended as the last paragraph
+    pub fn append_to(&self, message: &str) -> String {
+        format!("{}\n\n{}\n", message.trim_end(), self.trailers())
+    }
+
+    /// Read the trailers back from a commit message; `None` without a route trailer
+    pub fn parse(message: &str) -> Option<Provenance> {
+        let trailers = git2::message_trailers_strs(message).ok()?;
+        let mut provenance = Provenance::default();
+        for (key, value) in trailers.iter() {
+            let value = value.trim().to_string();
+            match key {
+                ROUTE_TRAILER => provenance.route_id = value,
+                SOURCE_REPO_TRAILER => provenance.source_repo_id = Some(value),
+                SOURCE_COMMIT_TRAILER => provenance.source_commits.push(value),
+                _ => {}
+            }
+        }
+        (!provenance.route_id.is_empty()).then_some(provenance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_back_appended_trailers() {
+        let provenance = Provenance {
+            route_id: "route-1".to_string(),
+            source_repo_id: Some("repo-1".to_string()),
+            source_commits: vec!["abc123".to_string(), "def456".to_string()],
+        };
+        let message = provenance.append_to("Fix the parser\n\nLonger description.\n");
+
+        assert_eq!(Provenance::parse(&message), Some(provenance));
+    }
+
+    #[test]
+    fn parse_needs_a_route_trailer() {
+        let message = format!("Fix the parser\n\n{}: abc123\n", SOURCE_COMMIT_TRAILER);
+        assert_eq!(Provenance::parse(&message), None);
+        assert_eq!(Provenance::parse("Fix the parser\n"), None);
+    }
+
+    #[test]
+    fn parse_ignores_other_trailers_and_trailer_lines_in_the_body() {
+        let message = format!(
+            "Fix the parser\n\n{}: not-a-trailer\nmore text\n\nSigned-off-by: A <a@example.com>\n{}: route-2\n",
+            ROUTE_TRAILER, ROUTE_TRAILER
+        );
+        let provenance = Provenance::parse(&message).unwrap();
+
+        assert_eq!(provenance.route_id, "route-2");
+        assert_eq!(provenance.source_repo_id, None);
+        assert!(provenance.source_commits.is_empty());
+    }
+}
@@ -0,0 +1,536 @@
+use crate::commands::repo_scan::{
+    normalize_remote_url, primary_remote_url, root_commit, scanned_repo, ScannedRepo,
+};
+use crate::database::entities::repositories;
+use anyhow::{anyhow, Result};
+use chrono::NaiveDateTime;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MissingRepo {
+    pub repo_id: String,
+    pub name: String,
+    pub path: String,
+    /// Already marked missing by an earlier rescan
+    pub missing_since: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ChangedRepo {
+    pub repo_id: String,
+    pub name: String,
+    pub path: String,
+    pub stored_remote: Option<String>,
+    pub scanned_remote: Option<String>,
+    pub remote_changed: bool,
+    /// Marked missing earlier but found again
+    pub reappeared: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DuplicateEntry {
+    /// `None` for a checkout found by the scan that is not registered
+    pub repo_id: Option<String>,
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DuplicateRemote {
+    /// Normalized remote URL shared by the entries
+    pub remote: String,
+    pub entries: Vec<DuplicateEntry>,
+}
+
+/// Difference between a rescan of a root and the repositories registered under it
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ReconcileReport {
+    pub root: String,
+    /// Checkouts found by the scan that are not registered
+    pub new: Vec<ScannedRepo>,
+    /// Registered repositories under the root whose checkout is gone
+    pub missing: Vec<MissingRepo>,
+    pub changed: Vec<ChangedRepo>,
+    /// The same remote checked out at more than one path, at least one of them under the root
+    pub duplicates: Vec<DuplicateRemote>,
+    pub unchanged: usize,
+}
+
+/// What to do about one finding of a reconcile report
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ReconcileAction {
+    /// Register a new checkout
+    Add { path: String },
+    /// Record that a repository's checkout is gone
+    MarkMissing { repo_id: String },
+    /// Take the remote from the checkout and clear the missing mark
+    Update { repo_id: String },
+    /// Fold duplicate repositories into `keep_id`; their routes and task steps move over
+    Merge {
+        keep_id: String,
+        merge_ids: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ReconcileSummary {
+    pub added: usize,
+    pub marked_missing: usize,
+    pub updated: usize,
+    pub merged: usize,
+}
+
+/// Compare `scanned` (a scan of `root`) with the registered `repos`.
+/// A registered checkout under the root that the scan skipped (e.g. inside `build`)
+/// is only reported missing when it no longer opens as a repository.
+pub fn diff(
+    root: &Path,
+    scanned: Vec<ScannedRepo>,
+    repos: &[repositories::Model],
+) -> ReconcileReport {
+    let mut report = ReconcileReport {
+        root: root.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let registered: HashSet<&str> = repos.iter().map(|r| r.local_path.as_str()).collect();
+    let found: HashMap<&str, &ScannedRepo> = scanned.iter().map(|r| (r.path.as_str(), r)).collect();
+
+    for repo in repos
+        .iter()
+        .filter(|r| Path::new(&r.local_path).starts_with(root))
+    {
+        let Some(scan) = found.get(repo.local_path.as_str()) else {
+            if Repository::open(&repo.local_path).is_err() {
+                report.missing.push(MissingRepo {
+                    repo_id: repo.id.clone(),
+                    name: repo.name.clone(),
+                    path: repo.local_path.clone(),
+                    missing_since: repo.missing_since,
+                });
+            } else {
+                report.unchanged += 1;
+            }
+            continue;
+        };
+
+        let stored = repo.remote_url.as_deref().filter(|u| !u.trim().is_empty());
+        let remote_changed = stored.map(normalize_remote_url)
+            != scan.remote_url.as_deref().map(normalize_remote_url);
+        let reappeared = repo.missing_since.is_some();
+        if remote_changed || reappeared {
+            report.changed.push(ChangedRepo {
+                repo_id: repo.id.clone(),
+                name: repo.name.clone(),
+                path: repo.local_path.clone(),
+                stored_remote: stored.map(String::from),
+                scanned_remote: scan.remote_url.clone(),
+                remote_changed,
+                reappeared,
+            });
+        } else {
+            report.unchanged += 1;
+        }
+    }
+
+    report.new = scanned
+        .iter()
+        .filter(|r| !registered.contains(r.path.as_str()))
+        .cloned()
+        .collect();
+    report.duplicates = duplicates(root, &scanned, repos);
+    report
+}
+
+/// Group present checkouts (registered ones that still exist plus new ones) by remote
+fn duplicates(
+    root: &Path,
+    scanned: &[ScannedRepo],
+    repos: &[repositories::Model],
+) -> Vec<DuplicateRemote> {
+    let scanned_remotes: HashMap<&str, Option<&str>> = scanned
+        .iter()
+        .map(|r| (r.path.as_str(), r.remote_url.as_deref()))
+        .collect();
+
+    let mut by_remote: BTreeMap<String, Vec<DuplicateEntry>> = BTreeMap::new();
+    for repo in repos {
+        // The checkout's current remote wins over the stored one
+        let remote = match scanned_remotes.get(repo.local_path.as_str()) {
+            Some(remote) => *remote,
+            None if Path::new(&repo.local_path).exists() => repo.remote_url.as_deref(),
+            None => continue,
+        };
+        if let Some(remote) = remote.filter(|u| !u.trim().is_empty()) {
+            by_remote
+                .entry(normalize_remote_url(remote))
+                .or_default()
+                .push(DuplicateEntry {
+                    repo_id: Some(repo.id.clone()),
+                    name: repo.name.clone(),
+                    path: repo.local_path.clone(),
+                });
+        }
+    }
+    let registered: HashSet<&str> = repos.iter().map(|r| r.local_path.as_str()).collect();
+    for scan in scanned
+        .iter()
+        .filter(|r| !registered.contains(r.path.as_str()))
+    {
+        if let Some(remote) = scan.remote_url.as_deref() {
+            by_remote
+                .entry(normalize_remote_url(remote))
+                .or_default()
+                .push(DuplicateEntry {
+                    repo_id: None,
+                    name: scan.name.clone(),
+                    path: scan.path.clone(),
+                });
+        }
+    }
+
+    by_remote
+        .into_iter()
+        .filter(|(_, entries)| {
+            entries.len() > 1 && entries.iter().any(|e| Path::new(&e.path).starts_with(root))
+        })
+        .map(|(remote, entries)| DuplicateRemote { remote, entries })
+        .collect()
+}
+
+/// An action checked against the disk and the database, ready to be stored
+pub enum PreparedAction {
+    Add {
+        repo: ScannedRepo,
+        root_commit: Option<String>,
+    },
+    MarkMissing {
+        repo: repositories::Model,
+    },
+    Update {
+        repo: repositories::Model,
+        remote_url: Option<String>,
+    },
+    Merge {
+        keep: repositories::Model,
+        merge_ids: Vec<String>,
+    },
+}
+
+/// Check `actions` before anything is stored: added paths must be unregistered checkouts
+/// under `root`, only checkouts that are gone can be marked missing, merged
+/// repositories must share the kept repository's remote, and a repository merged away
+/// is not touched by any other action
+pub fn prepare(
+    root: &Path,
+    actions: Vec<ReconcileAction>,
+    repos: &HashMap<String, repositories::Model>,
+    registered: &HashSet<String>,
+) -> Result<Vec<PreparedAction>> {
+    let find = |id: &str| {
+        repos
+            .get(id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Repository {} not found", id))
+    };
+    check_merges(&actions)?;
+    let mut added = HashSet::new();
+    actions
+        .into_iter()
+        .map(|action| match action {
+            ReconcileAction::Add { path } => {
+                let path = path.trim().to_string();
+                if !Path::new(&path).starts_with(root) {
+                    return Err(anyhow!("{} is not under {}", path, root.display()));
+                }
+                if registered.contains(&path) || !added.insert(path.clone()) {
+                    return Err(anyhow!("{} is already registered", path));
+                }
+                let checkout = Repository::open(&path)
+                    .map_err(|e| anyhow!("{}: not a git repository: {}", path, e.message()))?;
+                Ok(PreparedAction::Add {
+                    repo: scanned_repo(root, Path::new(&path)),
+                    root_commit: root_commit(&checkout),
+                })
+            }
+            ReconcileAction::MarkMissing { repo_id } => {
+                let repo = find(&repo_id)?;
+                if Repository::open(&repo.local_path).is_ok() {
+                    return Err(anyhow!(
+                        "{} is still present at {}",
+                        repo.name,
+                        repo.local_path
+                    ));
+                }
+                Ok(PreparedAction::MarkMissing { repo })
+            }
+            ReconcileAction::Update { repo_id } => {
+                let repo = find(&repo_id)?;
+                let checkout = Repository::open(&repo.local_path).map_err(|e| {
+                    anyhow!("{}: not a git repository: {}", repo.local_path, e.message())
+                })?;
+                Ok(PreparedAction::Update {
+                    remote_url: primary_remote_url(&checkout),
+                    repo,
+                })
+            }
+            ReconcileAction::Merge { keep_id, merge_ids } => {
+                let keep = find(&keep_id)?;
+                let wanted = current_remote(&keep);
+                for id in &merge_ids {
+                    let merged = find(id)?;
+                    if *id == keep_id {
+                        return Err(anyhow!("Cannot merge {} into itself", keep.name));
+                    }
+                    if wanted.is_none() || current_remote(&merged) != wanted {
+                        return Err(anyhow!(
+                            "{} and {} do not share a remote",
+                            merged.name,
+                            keep.name
+                        ));
+                    }
+                }
+                Ok(PreparedAction::Merge { keep, merge_ids })
+            }
+        })
+        .collect()
+}
+
+/// Each repository is merged away at most once, never kept by another merge,
+/// and not marked missing or updated in the same batch
+fn check_merges(actions: &[ReconcileAction]) -> Result<()> {
+    let mut merged_away = HashSet::new();
+    for action in actions {
+        if let ReconcileAction::Merge { merge_ids, .. } = action {
+            for id in merge_ids {
+                if !merged_away.insert(id.as_str()) {
+                    return Err(anyhow!("Repository {} is merged more than once", id));
+                }
+            }
+        }
+    }
+    for action in actions {
+        let (id, what) = match action {
+            ReconcileAction::Merge { keep_id, .. } => (keep_id, "kept"),
+            ReconcileAction::MarkMissing { repo_id } => (repo_id, "marked missing"),
+            ReconcileAction::Update { repo_id } => (repo_id, "updated"),
+            ReconcileAction::Add { .. } => continue,
+        };
+        if merged_away.contains(id.as_str()) {
+            return Err(anyhow!(
+                "Repository {} is merged away and cannot also be {}",
+                id,
+                what
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Normalized remote of the checkout, or the stored one when the checkout is gone
+fn current_remote(repo: &repositories::Model) -> Option<String> {
+    let remote = match Repository::open(&repo.local_path) {
+        Ok(checkout) => primary_remote_url(&checkout),
+        Err(_) => repo.remote_url.clone(),
+    };
+    remote
+        .filter(|u| !u.trim().is_empty())
+        .map(|u| normalize_remote_url(&u))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::repo_scan::discover_repositories;
+    use crate::core::test_support::{repository, Root};
+
+    const ACME: &str = "https://github.com/acme/app.git";
+
+    fn prepared(
+        root: &Root,
+        repos: &[repositories::Model],
+        actions: Vec<ReconcileAction>,
+    ) -> Result<Vec<PreparedAction>> {
+        let by_id = repos.iter().map(|r| (r.id.clone(), r.clone())).collect();
+        let registered = repos.iter().map(|r| r.local_path.clone()).collect();
+        prepare(&root.0, actions, &by_id, &registered)
+    }
+
+    fn error<T>(result: Result<T>) -> String {
+        match result {
+            Ok(_) => panic!("expected an error"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn diff_sorts_checkouts_into_new_missing_changed_and_unchanged() {
+        let root = Root::new();
+        let kept = root.checkout("kept", Some("git@github.com:acme/app.git"));
+        let moved = root.checkout("moved", Some("https://github.com/acme/moved.git"));
+        let back = root.checkout("back", Some("https://github.com/acme/back.git"));
+        let skipped = root.checkout("skipped", None);
+        let new = root.checkout("new", Some(ACME));
+
+        let mut returned = repository("back", &back, Some("https://github.com/acme/back.git"));
+        returned.missing_since = Some(chrono::Utc::now().naive_utc());
+        let repos = vec![
+            repository("kept", &kept, Some(ACME)),
+            repository("moved", &moved, Some("https://github.com/acme/old.git")),
+            returned,
+            repository("gone", &root.path("gone"), None),
+            repository("skipped", &skipped, None),
+            // Outside the root: neither missing nor unchanged
+            repository("elsewhere", "/nonexistent/elsewhere", None),
+        ];
+        // As if the scan had skipped the directory
+        let scanned: Vec<ScannedRepo> = discover_repositories(&root.0)
+            .into_iter()
+            .filter(|r| r.path != skipped)
+            .collect();
+
+        let report = diff(&root.0, scanned, &repos);
+
+        assert_eq!(report.unchanged, 2);
+        assert_eq!(
+            report
+                .new
+                .iter()
+                .map(|r| r.path.as_str())
+                .collect::<Vec<_>>(),
+            [new.as_str()]
+        );
+        assert_eq!(
+            report
+                .missing
+                .iter()
+                .map(|r| r.repo_id.as_str())
+                .collect::<Vec<_>>(),
+            ["gone"]
+        );
+
+        let mut changed = report.changed.clone();
+        changed.sort_by(|a, b| a.repo_id.cmp(&b.repo_id));
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[0].repo_id, "back");
+        assert!(changed[0].reappeared && !changed[0].remote_changed);
+        assert_eq!(changed[1].repo_id, "moved");
+        assert!(changed[1].remote_changed && !changed[1].reappeared);
+
+        assert_eq!(report.duplicates.len(), 1);
+        let duplicate = &report.duplicates[0];
+        assert_eq!(duplicate.remote, "github.com/acme/app");
+        let mut ids: Vec<Option<&str>> = duplicate
+            .entries
+            .iter()
+            .map(|e| e.repo_id.as_deref())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, [None, Some("kept")]);
+    }
+
+    #[test]
+    fn check_merges_rejects_repositories_merged_away_and_used_again() {
+        let merge = |keep: &str, merged: &[&str]| ReconcileAction::Merge {
+            keep_id: keep.to_string(),
+            merge_ids: merged.iter().map(|id| id.to_string()).collect(),
+        };
+
+        assert!(check_merges(&[merge("a", &["b"]), merge("c", &["d"])]).is_ok());
+        assert!(
+            error(check_merges(&[merge("a", &["b"]), merge("c", &["b"])]))
+                .contains("merged more than once")
+        );
+        assert!(
+            error(check_merges(&[merge("a", &["b"]), merge("b", &["c"])]))
+                .contains("cannot also be kept")
+        );
+        assert!(error(check_merges(&[
+            merge("a", &["b"]),
+            ReconcileAction::Update {
+                repo_id: "b".to_string()
+            },
+        ]))
+        .contains("cannot also be updated"));
+    }
+
+    #[test]
+    fn prepare_only_adds_unregistered_checkouts_under_the_root() {
+        let root = Root::new();
+        let registered = root.checkout("registered", None);
+        let new = root.checkout("new", Some(ACME));
+        let repos = vec![repository("registered", &registered, None)];
+        let add = |path: &str| ReconcileAction::Add {
+            path: path.to_string(),
+        };
+
+        assert!(
+            error(prepared(&root, &repos, vec![add("/elsewhere/repo")])).contains("is not under")
+        );
+        assert!(
+            error(prepared(&root, &repos, vec![add(&registered)])).contains("already registered")
+        );
+        assert!(error(prepared(&root, &repos, vec![add(&new), add(&new)]))
+            .contains("already registered"));
+        assert!(
+            error(prepared(&root, &repos, vec![add(&root.path("plain"))]))
+                .contains("not a git repository")
+        );
+
+        let actions = prepared(&root, &repos, vec![add(&new)]).unwrap();
+        match actions.as_slice() {
+            [PreparedAction::Add { repo, .. }] => {
+                assert_eq!(repo.path, new);
+                assert_eq!(repo.remote_url.as_deref(), Some(ACME));
+            }
+            _ => panic!("expected one add"),
+        }
+    }
+
+    #[test]
+    fn prepare_marks_only_gone_checkouts_missing() {
+        let root = Root::new();
+        let present = root.checkout("present", None);
+        let repos = vec![
+            repository("present", &present, None),
+            repository("gone", &root.path("gone"), None),
+        ];
+        let mark = |id: &str| ReconcileAction::MarkMissing {
+            repo_id: id.to_string(),
+        };
+
+        assert!(error(prepared(&root, &repos, vec![mark("present")])).contains("still present"));
+        assert!(error(prepared(&root, &repos, vec![mark("unknown")])).contains("not found"));
+        assert!(prepared(&root, &repos, vec![mark("gone")]).is_ok());
+    }
+
+    #[test]
+    fn prepare_merges_only_repositories_sharing_a_remote() {
+        let root = Root::new();
+        let keep = root.checkout("keep", Some(ACME));
+        let other = root.checkout("other", Some("https://github.com/acme/other.git"));
+        let repos = vec![
+            repository("keep", &keep, None),
+            repository("other", &other, None),
+            // Gone, so the stored remote is compared
+            repository(
+                "copy",
+                &root.path("copy"),
+                Some("git@github.com:Acme/App.git"),
+            ),
+        ];
+        let merge = |merged: &str| ReconcileAction::Merge {
+            keep_id: "keep".to_string(),
+            merge_ids: vec![merged.to_string()],
+        };
+
+        assert!(prepared(&root, &repos, vec![merge("copy")]).is_ok());
+        assert!(
+            error(prepared(&root, &repos, vec![merge("other")])).contains("do not share a remote")
+        );
+        assert!(error(prepared(&root, &repos, vec![merge("keep")])).contains("cannot also be kept"));
+    }
+}
@@ -0,0 +1,445 @@
+use crate::commands::repo_scan::{
+    discover_repositories, normalize_remote_url, primary_remote_url, root_commit, ScannedRepo,
+};
+use crate::database::entities::{repositories, settings};
+use anyhow::{anyhow, Result};
+use git2::Repository;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Directories searched for moved repositories, one per line; scan imports add their root
+pub const REPOSITORY_ROOTS_SETTING: &str = "repository_roots";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RelocationCandidate {
+    pub path: String,
+    pub name: String,
+    pub remote_url: Option<String>,
+    /// `None` when the root commit was not compared or could not be
+    pub root_commit_matches: Option<bool>,
+    /// The root commit should have been compared but is unknown for the repository or the
+    /// checkout (e.g. a shallow clone); such candidates are listed but never proposed
+    pub root_commit_unverified: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RelocationProposal {
+    pub repo_id: String,
+    pub name: String,
+    pub old_path: String,
+    /// The single best candidate; `None` when nothing matched or the match is ambiguous
+    pub proposed_path: Option<String>,
+    pub candidates: Vec<RelocationCandidate>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RelocationMove {
+    pub repo_id: String,
+    pub new_path: String,
+}
+
+/// Finds checkouts under the configured roots that a registered repository was moved to.
+/// Candidates share the stored remote URL; repositories without one are matched by root
+/// commit. With `match_root_commit` remote matches must also share the root commit.
+pub struct Relocator {
+    pub roots: Vec<PathBuf>,
+    pub match_root_commit: bool,
+}
+
+impl Relocator {
+    /// Propose new paths for `repos`; checkouts at `registered` paths are never proposed
+    pub fn propose(
+        &self,
+        repos: &[repositories::Model],
+        registered: &HashSet<String>,
+    ) -> Vec<RelocationProposal> {
+        let mut seen = HashSet::new();
+        let found: Vec<ScannedRepo> = self
+            .roots
+            .iter()
+            .filter(|root| root.is_dir())
+            .flat_map(|root| discover_repositories(root))
+            .filter(|r| !registered.contains(&r.path) && seen.insert(r.path.clone()))
+            .collect();
+
+        let mut roots_by_path: HashMap<String, Option<String>> = HashMap::new();
+        let mut root_of = |path: &str| {
+            roots_by_path
+                .entry(path.to_string())
+                .or_insert_with(|| Repository::open(path).ok().and_then(|r| root_commit(&r)))
+                .clone()
+        };
+
+        repos
+            .iter()
+            .map(|repo| {
+                let wanted = stored_remote(repo).map(normalize_remote_url);
+                let candidates: Vec<RelocationCandidate> = found
+                    .iter()
+                    .filter_map(|found| {
+                        let remote = found.remote_url.as_deref().map(normalize_remote_url);
+                        if wanted.is_some() && remote != wanted {
+                            return None;
+                        }
+                        let compare = self.match_root_commit || wanted.is_none();
+                        let root_commit_matches = match (&repo.root_commit, compare) {
+                            (Some(stored), true) => {
+                                root_of(&found.path).map(|root| root == *stored)
+                            }
+                            _ => None,
+                        };
+                        let root_commit_unverified = compare && root_commit_matches.is_none();
+                        // Without a remote only the root commit identifies the repository
+                        let matched = match wanted {
+                            Some(_) => root_commit_matches != Some(false),
+                            None => root_commit_matches == Some(true),
+                        };
+                        if !matched {
+                            return None;
+                        }
+                        Some(RelocationCandidate {
+                            path: found.path.clone(),
+                            name: found.name.clone(),
+                            remote_url: found.remote_url.clone(),
+                            root_commit_matches,
+                            root_commit_unverified,
+                        })
+                    })
+                    .collect();
+
+                RelocationProposal {
+                    repo_id: repo.id.clone(),
+                    name: repo.name.clone(),
+                    old_path: repo.local_path.clone(),
+                    proposed_path: best_candidate(&repo.local_path, &candidates),
+                    candidates,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The only candidate, else the only one with a matching root commit,
+/// else the only one whose directory kept its name. Unverified candidates are skipped.
+fn best_candidate(old_path: &str, candidates: &[RelocationCandidate]) -> Option<String> {
+    let candidates: Vec<&RelocationCandidate> = candidates
+        .iter()
+        .filter(|c| !c.root_commit_unverified)
+        .collect();
+    let only = |matching: Vec<&RelocationCandidate>| match matching.as_slice() {
+        [one] => Some(one.path.clone()),
+        _ => None,
+    };
+    let old_name = Path::new(old_path).file_name();
+    only(candidates.clone())
+        .or_else(|| {
+            only(
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|c| c.root_commit_matches == Some(true))
+                    .collect(),
+            )
+        })
+        .or_else(|| {
+            only(
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|c| old_name.is_some() && Path::new(&c.path).file_name() == old_name)
+                    .collect(),
+            )
+        })
+}
+
+fn stored_remote(repo: &repositories::Model) -> Option<&str> {
+    repo.remote_url.as_deref().filter(|u| !u.trim().is_empty())
+}
+
+/// Checked moves with the root commit of each new checkout, ready to be stored
+pub struct VerifiedMove {
+    pub repo: repositories::Model,
+    pub new_path: String,
+    pub root_commit: Option<String>,
+}
+
+/// Check that every new path is a git checkout that is not registered already and not
+/// claimed twice, and that it still looks like the same repository
+pub fn verify_moves(
+    moves: &[RelocationMove],
+    repos: &HashMap<String, repositories::Model>,
+    registered: &HashSet<String>,
+) -> Result<Vec<VerifiedMove>> {
+    let mut claimed = HashSet::new();
+    moves
+        .iter()
+        .map(|m| {
+            let repo = repos
+                .get(&m.repo_id)
+                .ok_or_else(|| anyhow!("Repository {} not found", m.repo_id))?;
+            let new_path = m.new_path.trim().to_string();
+            if !claimed.insert(new_path.clone()) {
+                return Err(anyhow!(
+                    "{} is claimed by more than one repository",
+                    new_path
+                ));
+            }
+            if registered.contains(&new_path) && new_path != repo.local_path {
+                return Err(anyhow!("{} is already registered", new_path));
+            }
+            let checkout = Repository::open(&new_path)
+                .map_err(|e| anyhow!("{}: not a git repository: {}", new_path, e.message()))?;
+
+            if let Some(stored) = stored_remote(repo) {
+                let remote = primary_remote_url(&checkout);
+                if remote.as_deref().map(normalize_remote_url) != Some(normalize_remote_url(stored))
+                {
+                    return Err(anyhow!(
+                        "{}: remote {} does not match {}",
+                        new_path,
+                        remote.as_deref().unwrap_or("(none)"),
+                        stored
+                    ));
+                }
+            }
+            let root = root_commit(&checkout);
+            if repo.root_commit.is_some() && root.is_some() && repo.root_commit != root {
+                return Err(anyhow!(
+                    "{}: root commit differs from {}",
+                    new_path,
+                    repo.name
+                ));
+            }
+
+            Ok(VerifiedMove {
+                repo: repo.clone(),
+                new_path,
+                root_commit: root,
+            })
+        })
+        .collect()
+}
+
+/// Store verified moves; the caller provides the transaction
+pub async fn apply_moves<C: ConnectionTrait>(
+    db: &C,
+    moves: Vec<VerifiedMove>,
+) -> Result<Vec<repositories::Model>> {
+    let mut updated = Vec::with_capacity(moves.len());
+    for m in moves {
+        let mut active: repositories::ActiveModel = m.repo.into();
+        active.local_path = Set(m.new_path);
+        active.missing_since = Set(None);
+        if m.root_commit.is_some() {
+            active.root_commit = Set(m.root_commit);
+        }
+        updated.push(active.update(db).await?);
+    }
+    Ok(updated)
+}
+
+/// Configured roots, in order
+pub async fn repository_roots<C: ConnectionTrait>(db: &C) -> Result<Vec<String>> {
+    Ok(settings::Entity::find_by_id(REPOSITORY_ROOTS_SETTING)
+        .one(db)
+        .await?
+        .map(|s| {
+            s.value
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Add `root` to the configured roots unless it is there already
+pub async fn remember_root<C: ConnectionTrait>(db: &C, root: &str) -> Result<()> {
+    let root = root.trim();
+    let mut roots = repository_roots(db).await?;
+    if root.is_empty() || roots.iter().any(|r| r == root) {
+        return Ok(());
+    }
+    roots.push(root.to_string());
+
+    let value = roots.join("\n");
+    match settings::Entity::find_by_id(REPOSITORY_ROOTS_SETTING)
+        .one(db)
+        .await?
+    {
+        Some(existing) => {
+            let mut active: settings::ActiveModel = existing.into();
+            active.value = Set(value);
+            active.update(db).await?;
+        }
+        None => {
+            settings::ActiveModel {
+                key: Set(REPOSITORY_ROOTS_SETTING.to_string()),
+                value: Set(value),
+            }
+            .insert(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Record the root commit of repositories registered before it was stored, while their
+/// checkouts are still where the database says. Returns how many were filled in.
+pub async fn backfill_root_commits<C: ConnectionTrait>(db: &C) -> Result<usize> {
+    let repos = repositories::Entity::find()
+        .filter(repositories::Column::RootCommit.is_null())
+        .filter(repositories::Column::MissingSince.is_null())
+        .all(db)
+        .await?;
+
+    let mut filled = 0;
+    for repo in repos {
+        let path = repo.local_path.clone();
+        let root = tokio::task::spawn_blocking(move || {
+            Repository::open(&path).ok().and_then(|r| root_commit(&r))
+        })
+        .await?;
+        let Some(root) = root else {
+            continue;
+        };
+        let mut active: repositories::ActiveModel = repo.into();
+        active.root_commit = Set(Some(root));
+        active.update(db).await?;
+        filled += 1;
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::{repository, Root};
+
+    const ACME: &str = "https://github.com/acme/app.git";
+
+    fn relocator(root: &Root, match_root_commit: bool) -> Relocator {
+        Relocator {
+            roots: vec![root.0.clone()],
+            match_root_commit,
+        }
+    }
+
+    fn model(path: &str, remote: Option<&str>, root_commit: Option<String>) -> repositories::Model {
+        repositories::Model {
+            root_commit,
+            ..repository("app", path, remote)
+        }
+    }
+
+    fn candidate(path: &str, root_commit_matches: Option<bool>) -> RelocationCandidate {
+        RelocationCandidate {
+            path: path.to_string(),
+            name: String::new(),
+            remote_url: None,
+            root_commit_matches,
+            root_commit_unverified: false,
+        }
+    }
+
+    #[test]
+    fn best_candidate_prefers_the_only_root_commit_match_then_the_kept_name() {
+        let old = "/old/app";
+        assert_eq!(
+            best_candidate(old, &[candidate("/new/copy", None)]).as_deref(),
+            Some("/new/copy")
+        );
+        assert_eq!(
+            best_candidate(
+                old,
+                &[
+                    candidate("/new/app", None),
+                    candidate("/new/copy", Some(true))
+                ]
+            )
+            .as_deref(),
+            Some("/new/copy")
+        );
+        assert_eq!(
+            best_candidate(
+                old,
+                &[candidate("/new/app", None), candidate("/new/copy", None)]
+            )
+            .as_deref(),
+            Some("/new/app")
+        );
+        assert_eq!(
+            best_candidate(old, &[candidate("/a/app", None), candidate("/b/app", None)]),
+            None
+        );
+        assert_eq!(best_candidate(old, &[]), None);
+    }
+
+    #[test]
+    fn best_candidate_skips_unverified_candidates() {
+        let mut unverified = candidate("/new/app", None);
+        unverified.root_commit_unverified = true;
+        assert_eq!(best_candidate("/old/app", &[unverified.clone()]), None);
+        assert_eq!(
+            best_candidate("/old/app", &[unverified, candidate("/new/copy", None)]).as_deref(),
+            Some("/new/copy")
+        );
+    }
+
+    #[test]
+    fn propose_matches_the_stored_remote_and_skips_registered_paths() {
+        let root = Root::new();
+        root.checkout("app", Some("git@github.com:Acme/App.git"));
+        let registered = HashSet::from([root.checkout("registered", Some(ACME))]);
+        root.checkout("other", Some("https://github.com/acme/other.git"));
+
+        let proposals =
+            relocator(&root, false).propose(&[model("/old/app", Some(ACME), None)], &registered);
+
+        assert_eq!(proposals.len(), 1);
+        let proposal = &proposals[0];
+        assert_eq!(proposal.old_path, "/old/app");
+        assert_eq!(proposal.proposed_path, Some(root.path("app")));
+        assert_eq!(proposal.candidates.len(), 1);
+        assert_eq!(proposal.candidates[0].root_commit_matches, None);
+    }
+
+    #[test]
+    fn propose_without_a_remote_matches_the_root_commit() {
+        let root = Root::new();
+        root.checkout("moved", None);
+        let wanted = Some(root.root_commit("moved", "first"));
+        root.checkout("unrelated", None);
+        root.root_commit("unrelated", "another first");
+        root.checkout("empty", None);
+
+        let proposals =
+            relocator(&root, false).propose(&[model("/old/app", None, wanted)], &HashSet::new());
+
+        let proposal = &proposals[0];
+        assert_eq!(proposal.proposed_path, Some(root.path("moved")));
+        assert_eq!(proposal.candidates.len(), 1);
+        assert_eq!(proposal.candidates[0].root_commit_matches, Some(true));
+    }
+
+    #[test]
+    fn propose_lists_but_never_proposes_unverifiable_candidates() {
+        let root = Root::new();
+        root.checkout("source", None);
+        let stored = Some(root.root_commit("source", "first"));
+        std::fs::remove_dir_all(root.0.join("source")).unwrap();
+        // Same remote, but no commits to compare
+        root.checkout("app", Some(ACME));
+
+        let proposals = relocator(&root, true)
+            .propose(&[model("/old/app", Some(ACME), stored)], &HashSet::new());
+
+        let proposal = &proposals[0];
+        assert_eq!(proposal.proposed_path, None);
+        assert_eq!(proposal.candidates.len(), 1);
+        assert!(proposal.candidates[0].root_commit_unverified);
+    }
+}
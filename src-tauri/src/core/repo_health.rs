@@ -0,0 +1,318 @@
+use crate::commands::git_branches::branch_exists;
+use crate::commands::repo_scan::normalize_remote_url;
+use crate::database::entities::repositories;
+use git2::{BranchType, ObjectType, Oid, Repository, StatusOptions, TreeWalkMode, TreeWalkResult};
+use serde::Serialize;
+use std::path::Path;
+
+/// Untracked files at least this large are reported
+const LARGE_FILE_BYTES: u64 = 50 * 1024 * 1024;
+/// Objects or files listed by name before the rest are only counted
+const MAX_LISTED: usize = 5;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HealthIssue {
+    /// Stable identifier, e.g. "path_missing" or "remote_mismatch"
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RepoHealth {
+    pub repo_id: String,
+    pub name: String,
+    pub path: String,
+    /// Worst severity among the issues; `None` when healthy
+    pub severity: Option<Severity>,
+    pub issues: Vec<HealthIssue>,
+}
+
+/// Checks a registered repository against its checkout on disk.
+/// `deep` reads every object in the object database to find corruption,
+/// which can take a while on large repositories.
+pub struct RepoHealthChecker {
+    pub deep: bool,
+}
+
+impl RepoHealthChecker {
+    pub fn check(&self, model: &repositories::Model) -> RepoHealth {
+        let issues = self.issues(model);
+        RepoHealth {
+            repo_id: model.id.clone(),
+            name: model.name.clone(),
+            path: model.local_path.clone(),
+            severity: issues.iter().map(|i| i.severity).max(),
+            issues,
+        }
+    }
+
+    fn issues(&self, model: &repositories::Model) -> Vec<HealthIssue> {
+        let path = Path::new(&model.local_path);
+        if !path.exists() {
+            return vec![issue(
+                "path_missing",
+                Severity::Error,
+                "Local path does not exist; the checkout was moved or deleted".to_string(),
+                "Relocate the repository to its new path, or remove it",
+            )];
+        }
+        let repo = match Repository::open(path) {
+            Ok(repo) => repo,
+            Err(e) => {
+                return vec![issue(
+                    "not_a_repository",
+                    Severity::Error,
+                    format!("Not a git repository anymore: {}", e.message()),
+                    "Clone the repository again at this path, or remove it",
+                )]
+            }
+        };
+
+        let mut issues = Vec::new();
+        issues.extend(remote_mismatch(&repo, model));
+        issues.extend(missing_branch(&repo, model));
+        issues.extend(no_upstream(&repo));
+        issues.extend(large_untracked(&repo));
+        issues.extend(merged_branches(&repo, model));
+        issues.extend(corrupt_objects(&repo, self.deep));
+        issues
+    }
+}
+
+fn issue(code: &str, severity: Severity, message: String, suggestion: &str) -> HealthIssue {
+    HealthIssue {
+        code: code.to_string(),
+        severity,
+        message,
+        suggestion: suggestion.to_string(),
+    }
+}
+
+/// The stored remote URL matches none of the checkout's remotes
+fn remote_mismatch(repo: &Repository, model: &repositories::Model) -> Option<HealthIssue> {
+    let stored = model
+        .remote_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())?;
+    let names = repo.remotes().ok()?;
+    let urls: Vec<String> = names
+        .iter()
+        .flatten()
+        .filter_map(|name| repo.find_remote(name).ok()?.url().map(String::from))
+        .collect();
+    if urls.is_empty() {
+        return Some(issue(
+            "remote_mismatch",
+            Severity::Warning,
+            format!("No remote configured, expected {}", stored),
+            "Add the stored remote as origin, or clear the stored remote URL",
+        ));
+    }
+
+    let wanted = normalize_remote_url(stored);
+    if urls.iter().any(|u| normalize_remote_url(u) == wanted) {
+        return None;
+    }
+    Some(issue(
+        "remote_mismatch",
+        Severity::Warning,
+        format!(
+            "Stored remote {} does not match the checkout ({})",
+            stored,
+            urls.join(", ")
+        ),
+        "Update the stored remote URL, or point origin at the stored one",
+    ))
+}
+
+/// The stored branch exists neither locally nor on a remote
+fn missing_branch(repo: &Repository, model: &repositories::Model) -> Option<HealthIssue> {
+    let branch = model.branch.as_deref().filter(|b| !b.is_empty())?;
+    if branch_exists(repo, branch) {
+        return None;
+    }
+    Some(issue(
+        "missing_branch",
+        Severity::Warning,
+        format!("Stored branch '{}' no longer exists", branch),
+        "Pick another branch for the repository, or fetch if it was only created remotely",
+    ))
+}
+
+/// The checked out branch tracks nothing, so pull and push need explicit arguments
+fn no_upstream(repo: &Repository) -> Option<HealthIssue> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let name = head.shorthand()?.to_string();
+    let branch = repo.find_branch(&name, BranchType::Local).ok()?;
+    if branch.upstream().is_ok() || repo.remotes().map(|r| r.is_empty()).unwrap_or(true) {
+        return None;
+    }
+    Some(issue(
+        "no_upstream",
+        Severity::Info,
+        format!("Branch '{}' has no upstream", name),
+        "Set an upstream branch, or push with an upstream",
+    ))
+}
+
+fn large_untracked(repo: &Repository) -> Option<HealthIssue> {
+    let workdir = repo.workdir()?;
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let statuses = repo.statuses(Some(&mut options)).ok()?;
+
+    let large: Vec<String> = statuses
+        .iter()
+        .filter(|entry| entry.status().is_wt_new())
+        .filter_map(|entry| {
+            let path = entry.path()?.to_string();
+            let size = std::fs::metadata(workdir.join(&path)).ok()?.len();
+            (size >= LARGE_FILE_BYTES).then(|| format!("{} ({} MB)", path, size / 1024 / 1024))
+        })
+        .collect();
+    if large.is_empty() {
+        return None;
+    }
+    Some(issue(
+        "large_untracked",
+        Severity::Warning,
+        format!("Large untracked files: {}", listed(&large)),
+        "Ignore them, move them out of the checkout, or track them with Git LFS",
+    ))
+}
+
+/// Local branches whose commits are all on the main branch
+fn merged_branches(repo: &Repository, model: &repositories::Model) -> Option<HealthIssue> {
+    let (main, main_tip) = main_branch(repo, model)?;
+    let merged: Vec<String> = repo
+        .branches(Some(BranchType::Local))
+        .ok()?
+        .flatten()
+        .filter(|(branch, _)| !branch.is_head())
+        .filter_map(|(branch, _)| {
+            let name = branch.name().ok()??.to_string();
+            let tip = branch.get().target()?;
+            let merged = name != main
+                && (tip == main_tip || repo.graph_descendant_of(main_tip, tip).unwrap_or(false));
+            merged.then_some(name)
+        })
+        .collect();
+    if merged.is_empty() {
+        return None;
+    }
+    Some(issue(
+        "merged_branches",
+        Severity::Info,
+        format!("Branches merged into {}: {}", main, listed(&merged)),
+        "Delete the merged branches",
+    ))
+}
+
+/// Without `deep` only the objects HEAD needs are read: its commit and every tree and
+/// blob reachable from its tree
+fn corrupt_objects(repo: &Repository, deep: bool) -> Option<HealthIssue> {
+    let mut corrupt = Vec::new();
+    if deep {
+        let odb = repo.odb().ok()?;
+        let mut oids = Vec::new();
+        if let Err(e) = odb.foreach(|oid| {
+            oids.push(*oid);
+            true
+        }) {
+            corrupt.push(format!("object database: {}", e.message()));
+        }
+        corrupt.extend(
+            oids.into_iter()
+                .filter(|oid| odb.read(*oid).is_err())
+                .map(|oid| oid.to_string()),
+        );
+    } else if let Ok(head) = repo.head() {
+        let tree = head.peel_to_commit().and_then(|commit| commit.tree());
+        let mut unreadable = None;
+        let walked = tree.and_then(|tree| {
+            tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+                // Submodule commits live in another repository
+                if entry.kind() == Some(ObjectType::Commit) {
+                    return TreeWalkResult::Ok;
+                }
+                match entry.to_object(repo) {
+                    Ok(_) => TreeWalkResult::Ok,
+                    Err(e) => {
+                        let name = entry.name().unwrap_or_default();
+                        unreadable = Some(format!("{}{}: {}", root, name, e.message()));
+                        TreeWalkResult::Abort
+                    }
+                }
+            })
+        });
+        if let Some(path) = unreadable {
+            corrupt.push(format!("HEAD: {}", path));
+        } else if let Err(e) = walked {
+            corrupt.push(format!("HEAD: {}", e.message()));
+        }
+    }
+    if corrupt.is_empty() {
+        return None;
+    }
+    Some(issue(
+        "corrupt_objects",
+        Severity::Error,
+        format!("Unreadable objects: {}", listed(&corrupt)),
+        "Run `git fsck`; fetch again or re-clone to restore the objects",
+    ))
+}
+
+/// The branch merged branches are compared against: the stored branch, the remote's
+/// default branch, or `main`/`master`
+fn main_branch(repo: &Repository, model: &repositories::Model) -> Option<(String, Oid)> {
+    let remote_default = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|r| r.symbolic_target().map(String::from))
+        .and_then(|t| t.strip_prefix("refs/remotes/origin/").map(String::from));
+    let candidates = model
+        .branch
+        .clone()
+        .filter(|b| !b.is_empty())
+        .into_iter()
+        .chain(remote_default)
+        .chain(["main".to_string(), "master".to_string()]);
+
+    for name in candidates {
+        let tip = repo
+            .find_branch(&name, BranchType::Local)
+            .or_else(|_| repo.find_branch(&format!("origin/{}", name), BranchType::Remote))
+            .ok()
+            .and_then(|b| b.get().target());
+        if let Some(tip) = tip {
+            return Some((name, tip));
+        }
+    }
+    None
+}
+
+fn listed(items: &[String]) -> String {
+    let mut text = items
+        .iter()
+        .take(MAX_LISTED)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > MAX_LISTED {
+        text.push_str(&format!(" and {} more", items.len() - MAX_LISTED));
+    }
+    text
+}
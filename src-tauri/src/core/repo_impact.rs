@@ -0,0 +1,403 @@
+use crate::commands::repo::descendant_repo_group_ids;
+use crate::core::orchestrator::{GitParams, SyncParams};
+use crate::database::entities::{
+    repo_groups, repositories, route_groups, routes, task_steps, tasks, workspace_config,
+};
+use anyhow::{anyhow, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ImpactedItem {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ImpactedRoute {
+    pub id: String,
+    pub name: String,
+    pub source_id: Option<String>,
+    pub target_id: Option<String>,
+    pub role: String, // "source" | "target" | "both"
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ImpactedTaskStep {
+    pub task_id: Option<String>,
+    pub task_name: Option<String>,
+    pub step_id: i32,
+    pub step_order: i32,
+    pub action_type: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ImpactedWorkspace {
+    pub group_id: String,
+    pub group_name: Option<String>,
+    pub path: String,
+}
+
+/// What a repository or group deletion would touch
+#[derive(Serialize, Debug, Default)]
+pub struct DeletionImpact {
+    /// Repositories that would be deleted
+    pub repositories: Vec<ImpactedItem>,
+    /// Repository groups that would be deleted
+    pub groups: Vec<ImpactedItem>,
+    /// Repositories kept but moved out of a deleted group
+    pub ungrouped_repositories: Vec<ImpactedItem>,
+    pub routes: Vec<ImpactedRoute>,
+    pub task_steps: Vec<ImpactedTaskStep>,
+    pub workspace_configs: Vec<ImpactedWorkspace>,
+}
+
+impl DeletionImpact {
+    /// Routes, tasks or workspaces depend on what is being deleted
+    pub fn needs_confirmation(&self) -> bool {
+        !self.routes.is_empty() || !self.task_steps.is_empty() || !self.workspace_configs.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Deleting affects {} route(s), {} task step(s) and {} workspace configuration(s); confirm to continue",
+            self.routes.len(),
+            self.task_steps.len(),
+            self.workspace_configs.len()
+        )
+    }
+}
+
+/// What to do with routes that reference a deleted repository
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteAction {
+    /// Drop the routes (the previous behaviour)
+    Delete,
+    /// Keep the routes, detached from the deleted repository and excluded from syncing
+    Archive,
+    /// Point the routes at another repository
+    Reassign(String),
+}
+
+impl RouteAction {
+    pub fn parse(action: Option<&str>, reassign_to: Option<String>) -> Result<Self, String> {
+        match action.unwrap_or("delete") {
+            "delete" => Ok(RouteAction::Delete),
+            "archive" => Ok(RouteAction::Archive),
+            "reassign" => reassign_to
+                .filter(|id| !id.is_empty())
+                .map(RouteAction::Reassign)
+                .ok_or_else(|| "reassign_to is required to reassign routes".to_string()),
+            other => Err(format!("Unknown route action: {}", other)),
+        }
+    }
+}
+
+pub struct RepoImpactAnalyzer;
+
+impl RepoImpactAnalyzer {
+    /// Impact of deleting the given repositories
+    pub async fn analyze<C: ConnectionTrait>(
+        db: &C,
+        repo_ids: &[String],
+    ) -> Result<DeletionImpact> {
+        let ids: HashSet<&str> = repo_ids.iter().map(|s| s.as_str()).collect();
+        let repos: Vec<repositories::Model> = repositories::Entity::find()
+            .filter(repositories::Column::Id.is_in(repo_ids.to_vec()))
+            .all(db)
+            .await?;
+
+        let mut impact = DeletionImpact {
+            repositories: repos
+                .iter()
+                .map(|r| ImpactedItem {
+                    id: r.id.clone(),
+                    name: r.name.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        // Routes
+        for route in Self::affected_routes(db, repo_ids).await? {
+            let as_source = route
+                .main_repo_id
+                .as_deref()
+                .is_some_and(|id| ids.contains(id));
+            let as_target = route
+                .slave_repo_id
+                .as_deref()
+                .is_some_and(|id| ids.contains(id));
+            let role = match (as_source, as_target) {
+                (true, true) => "both",
+                (true, false) => "source",
+                _ => "target",
+            };
+            impact.routes.push(ImpactedRoute {
+                id: route.id,
+                name: route.name,
+                source_id: route.main_repo_id,
+                target_id: route.slave_repo_id,
+                role: role.to_string(),
+            });
+        }
+
+        // Task steps running git on the repositories or syncing affected routes
+        let route_names: HashMap<&str, &str> = impact
+            .routes
+            .iter()
+            .map(|r| (r.id.as_str(), r.name.as_str()))
+            .collect();
+        let task_names: HashMap<String, String> = tasks::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|t| (t.id, t.name))
+            .collect();
+
+        for step in task_steps::Entity::find().all(db).await? {
+            let params = step.params.as_deref().unwrap_or_default();
+            let reason = match step.action_type.as_str() {
+                "git" => serde_json::from_str::<GitParams>(params)
+                    .ok()
+                    .filter(|p| ids.contains(p.repo_id.as_str()))
+                    .map(|p| format!("Runs git {} on a deleted repository", p.operation)),
+                "sync" => serde_json::from_str::<SyncParams>(params)
+                    .ok()
+                    .and_then(|p| route_names.get(p.route_id.as_str()).copied())
+                    .map(|name| format!("Syncs route '{}'", name)),
+                _ => None,
+            };
+            let reason = reason.or_else(|| {
+                step.target_id
+                    .as_deref()
+                    .filter(|id| ids.contains(id) || route_names.contains_key(id))
+                    .map(|_| "Targets an affected repository or route".to_string())
+            });
+
+            if let Some(reason) = reason {
+                impact.task_steps.push(ImpactedTaskStep {
+                    task_name: step
+                        .task_id
+                        .as_ref()
+                        .and_then(|id| task_names.get(id).cloned()),
+                    task_id: step.task_id,
+                    step_id: step.id,
+                    step_order: step.step_order,
+                    action_type: step.action_type,
+                    reason,
+                });
+            }
+        }
+
+        // Workspace configs opening a path inside one of the repositories
+        let group_names: HashMap<String, String> = route_groups::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|g| (g.id, g.name))
+            .collect();
+
+        for config in workspace_config::Entity::find().all(db).await? {
+            for path in [&config.source_path, &config.target_path]
+                .into_iter()
+                .flatten()
+            {
+                if !path.is_empty()
+                    && repos
+                        .iter()
+                        .any(|r| Path::new(path).starts_with(&r.local_path))
+                {
+                    impact.workspace_configs.push(ImpactedWorkspace {
+                        group_id: config.group_id.clone(),
+                        group_name: group_names.get(&config.group_id).cloned(),
+                        path: path.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(impact)
+    }
+
+    /// Impact of deleting a repository group and its subgroups.
+    /// Repositories inside are only deleted with `delete_repositories`; otherwise they become ungrouped.
+    pub async fn analyze_group<C: ConnectionTrait>(
+        db: &C,
+        group_id: &str,
+        delete_repositories: bool,
+    ) -> Result<DeletionImpact> {
+        let all_groups = repo_groups::Entity::find().all(db).await?;
+        let group_ids = descendant_repo_group_ids(&all_groups, group_id);
+        let repos: Vec<repositories::Model> = repositories::Entity::find()
+            .filter(repositories::Column::GroupId.is_in(group_ids.iter().cloned()))
+            .all(db)
+            .await?;
+
+        let mut impact = if delete_repositories {
+            Self::analyze(db, &repos.iter().map(|r| r.id.clone()).collect::<Vec<_>>()).await?
+        } else {
+            DeletionImpact {
+                ungrouped_repositories: repos
+                    .iter()
+                    .map(|r| ImpactedItem {
+                        id: r.id.clone(),
+                        name: r.name.clone(),
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+        };
+
+        impact.groups = all_groups
+            .iter()
+            .filter(|g| group_ids.contains(&g.id))
+            .map(|g| ImpactedItem {
+                id: g.id.clone(),
+                name: g.name.clone(),
+            })
+            .collect();
+
+        Ok(impact)
+    }
+
+    /// Delete, archive or reassign the routes referencing `repo_ids`
+    pub async fn apply_route_action<C: ConnectionTrait>(
+        db: &C,
+        repo_ids: &[String],
+        action: &RouteAction,
+    ) -> Result<()> {
+        let affected = Self::affected_routes(db, repo_ids).await?;
+        if affected.is_empty() {
+            return Ok(());
+        }
+
+        if let RouteAction::Delete = action {
+            routes::Entity::delete_many()
+                .filter(routes::Column::Id.is_in(affected.iter().map(|r| r.id.clone())))
+                .exec(db)
+                .await?;
+            return Ok(());
+        }
+
+        let repo_names: HashMap<String, String> = repositories::Entity::find()
+            .filter(repositories::Column::Id.is_in(repo_ids.to_vec()))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|r| (r.id, r.name))
+            .collect();
+        let now = chrono::Utc::now().naive_utc();
+
+        for route in affected {
+            let deleted =
+                |id: &Option<String>| id.as_ref().is_some_and(|id| repo_names.contains_key(id));
+            let (main_deleted, slave_deleted) =
+                (deleted(&route.main_repo_id), deleted(&route.slave_repo_id));
+            let mut active: routes::ActiveModel = route.clone().into();
+
+            match action {
+                RouteAction::Reassign(new_repo_id) => {
+                    let main = if main_deleted {
+                        Some(new_repo_id.clone())
+                    } else {
+                        route.main_repo_id.clone()
+                    };
+                    let slave = if slave_deleted {
+                        Some(new_repo_id.clone())
+                    } else {
+                        route.slave_repo_id.clone()
+                    };
+                    if main == slave {
+                        return Err(anyhow!(
+                            "Route '{}' would sync a repository into itself",
+                            route.name
+                        ));
+                    }
+                    active.main_repo_id = Set(main);
+                    active.slave_repo_id = Set(slave);
+                }
+                RouteAction::Archive => {
+                    let names: Vec<&str> = [&route.main_repo_id, &route.slave_repo_id]
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|id| repo_names.get(id).map(|n| n.as_str()))
+                        .collect();
+                    active.archived_at = Set(Some(now));
+                    active.archived_reason = Set(Some(format!(
+                        "Repository '{}' was deleted",
+                        names.join("', '")
+                    )));
+                    // Detach from the deleted repository so the foreign key cascade keeps the route
+                    if main_deleted {
+                        active.main_repo_id = Set(None);
+                    }
+                    if slave_deleted {
+                        active.slave_repo_id = Set(None);
+                    }
+                }
+                RouteAction::Delete => unreachable!("handled above"),
+            }
+
+            active.updated_at = Set(now);
+            active.update(db).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Point git task steps and step targets on the given repositories at `new_repo_id`
+    pub async fn reassign_task_steps<C: ConnectionTrait>(
+        db: &C,
+        repo_ids: &[String],
+        new_repo_id: &str,
+    ) -> Result<()> {
+        for step in task_steps::Entity::find().all(db).await? {
+            let mut params = None;
+            if step.action_type == "git" {
+                if let Ok(mut git) =
+                    serde_json::from_str::<GitParams>(step.params.as_deref().unwrap_or_default())
+                {
+                    if repo_ids.contains(&git.repo_id) {
+                        git.repo_id = new_repo_id.to_string();
+                        params = Some(serde_json::to_string(&git)?);
+                    }
+                }
+            }
+            let retarget = step
+                .target_id
+                .as_ref()
+                .is_some_and(|id| repo_ids.contains(id));
+            if params.is_none() && !retarget {
+                continue;
+            }
+
+            let mut active: task_steps::ActiveModel = step.into();
+            if let Some(params) = params {
+                active.params = Set(Some(params));
+            }
+            if retarget {
+                active.target_id = Set(Some(new_repo_id.to_string()));
+            }
+            active.update(db).await?;
+        }
+        Ok(())
+    }
+
+    async fn affected_routes<C: ConnectionTrait>(
+        db: &C,
+        repo_ids: &[String],
+    ) -> Result<Vec<routes::Model>> {
+        Ok(routes::Entity::find()
+            .filter(
+                routes::Column::MainRepoId
+                    .is_in(repo_ids.to_vec())
+                    .or(routes::Column::SlaveRepoId.is_in(repo_ids.to_vec())),
+            )
+            .all(db)
+            .await?)
+    }
+}
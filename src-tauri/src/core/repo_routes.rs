@@ -0,0 +1,189 @@
+use crate::core::route_bundle::{BundleFormat, RepoIndex, RouteBundle};
+use crate::database::entities::{repo_groups, repositories, routes};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// File names looked up in the root of every registered repository
+pub const ROUTE_FILE_NAMES: [&str; 2] = [".sourcebridge.yml", ".sourcebridge.yaml"];
+
+/// Prefix of the synthetic ids given to repository-defined routes
+const ID_PREFIX: &str = "repo:";
+
+/// A route declared in a repository's `.sourcebridge.yml`.
+/// The file uses the route bundle format; `source` defaults to the repository itself.
+#[derive(Debug, Clone)]
+pub struct RepoDefinedRoute {
+    pub route: routes::Model,
+    pub repo_id: String,
+    pub repo_name: String,
+}
+
+struct CachedRouteFile {
+    path: PathBuf,
+    modified: SystemTime,
+    bundle: Result<RouteBundle, String>,
+}
+
+// Parsed route files keyed by repository id. Entries are re-read whenever the
+// file's modification time changes, so edits show up on the next lookup.
+static ROUTE_FILE_CACHE: Lazy<Mutex<HashMap<String, CachedRouteFile>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub struct RepoRouteRegistry;
+
+impl RepoRouteRegistry {
+    pub fn is_repo_defined(route_id: &str) -> bool {
+        route_id.starts_with(ID_PREFIX)
+    }
+
+    /// Error returned by commands that try to modify a repository-defined route
+    pub fn ensure_mutable(route_id: &str) -> Result<(), String> {
+        if Self::is_repo_defined(route_id) {
+            return Err(
+                "This route is defined in the repository's .sourcebridge.yml and is read-only"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Load the routes declared by every registered repository
+    pub async fn load_all(db: &DatabaseConnection) -> Result<Vec<RepoDefinedRoute>> {
+        let groups = repo_groups::Entity::find().all(db).await?;
+        let repos = repositories::Entity::find().all(db).await?;
+        let index = RepoIndex::new(&groups, repos.clone());
+
+        let mut result = Vec::new();
+        let mut cache = ROUTE_FILE_CACHE.lock().unwrap();
+        // Forget repositories that were removed since the last load
+        cache.retain(|repo_id, _| repos.iter().any(|r| &r.id == repo_id));
+
+        for repo in &repos {
+            let Some(path) = find_route_file(Path::new(&repo.local_path)) else {
+                cache.remove(&repo.id);
+                continue;
+            };
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+
+            let stale = cache
+                .get(&repo.id)
+                .map(|c| c.path != path || c.modified != modified)
+                .unwrap_or(true);
+            if stale {
+                let bundle = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|content| {
+                        RouteBundle::parse(&content, BundleFormat::Yaml).map_err(|e| e.to_string())
+                    })
+                    .and_then(|bundle| unique_names(&bundle).map(|_| bundle));
+                if let Err(e) = &bundle {
+                    eprintln!("[RepoRouteRegistry] Failed to load {:?}: {}", path, e);
+                }
+                cache.insert(
+                    repo.id.clone(),
+                    CachedRouteFile {
+                        path: path.clone(),
+                        modified,
+                        bundle,
+                    },
+                );
+            }
+
+            let Some(CachedRouteFile {
+                bundle: Ok(bundle),
+                modified,
+                ..
+            }) = cache.get(&repo.id)
+            else {
+                continue;
+            };
+
+            let updated_at = chrono::DateTime::<chrono::Utc>::from(*modified).naive_utc();
+            for declared in &bundle.routes {
+                let source_id = match &declared.source {
+                    Some(reference) => index.resolve(reference).map(|r| r.id.clone()),
+                    None => Some(repo.id.clone()),
+                };
+                let target_id = declared
+                    .target
+                    .as_ref()
+                    .and_then(|reference| index.resolve(reference))
+                    .map(|r| r.id.clone());
+
+                result.push(RepoDefinedRoute {
+                    route: routes::Model {
+                        id: format!("{}{}:{}", ID_PREFIX, repo.id, declared.name),
+                        group_id: None,
+                        name: declared.name.clone(),
+                        description: declared.description.clone(),
+                        main_repo_id: source_id,
+                        slave_repo_id: target_id,
+                        last_sync_status: None,
+                        last_sync_time: None,
+                        updated_at,
+                        mappings: Some(serde_json::to_string(&declared.mappings)?),
+                        archived_at: None,
+                        archived_reason: None,
+                    },
+                    repo_id: repo.id.clone(),
+                    repo_name: repo.name.clone(),
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Look up a single repository-defined route by its synthetic id
+    pub async fn find(db: &DatabaseConnection, route_id: &str) -> Result<Option<routes::Model>> {
+        if !Self::is_repo_defined(route_id) {
+            return Ok(None);
+        }
+        Ok(Self::load_all(db)
+            .await?
+            .into_iter()
+            .find(|r| r.route.id == route_id)
+            .map(|r| r.route))
+    }
+}
+
+/// Find a route by id, whether it is stored in the database or declared in a repository
+pub async fn find_route(db: &DatabaseConnection, route_id: &str) -> Result<Option<routes::Model>> {
+    if RepoRouteRegistry::is_repo_defined(route_id) {
+        return RepoRouteRegistry::find(db, route_id).await;
+    }
+    Ok(routes::Entity::find_by_id(route_id).one(db).await?)
+}
+
+/// Same as `find_route`, but treats a missing route as an error
+pub async fn get_route(db: &DatabaseConnection, route_id: &str) -> Result<routes::Model> {
+    find_route(db, route_id)
+        .await?
+        .ok_or_else(|| anyhow!("Route not found"))
+}
+
+fn find_route_file(repo_root: &Path) -> Option<PathBuf> {
+    ROUTE_FILE_NAMES
+        .iter()
+        .map(|name| repo_root.join(name))
+        .find(|p| p.is_file())
+}
+
+/// Route names make up the synthetic ids, so a file must not declare one twice
+fn unique_names(bundle: &RouteBundle) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for route in &bundle.routes {
+        if !seen.insert(route.name.as_str()) {
+            return Err(format!("Route '{}' is declared more than once", route.name));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use crate::commands::repo_scan::normalize_remote_url;
+use crate::commands::route::MappingRule;
+use crate::database::entities::{repo_groups, repositories, route_groups};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Current version of the route bundle file format
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Machine-independent reference to a repository.
+/// `relative_path` is the repository's place in the repository tree
+/// (group names followed by the repository name, e.g. `github.com/acme/web`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RepoRef {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_path: Option<String>,
+}
+
+impl RepoRef {
+    pub fn describe(&self) -> String {
+        self.remote_url
+            .clone()
+            .or_else(|| self.relative_path.clone())
+            .unwrap_or_else(|| "<empty reference>".to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundledRoute {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Route group path from the root, e.g. `["Frontend", "Shared"]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<RepoRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<RepoRef>,
+    #[serde(default)]
+    pub mappings: Vec<MappingRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RouteBundle {
+    pub version: u32,
+    #[serde(default)]
+    pub routes: Vec<BundledRoute>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BundleFormat {
+    Yaml,
+    Json,
+}
+
+impl BundleFormat {
+    /// `.json` files are read and written as JSON, everything else as YAML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => BundleFormat::Json,
+            _ => BundleFormat::Yaml,
+        }
+    }
+}
+
+impl RouteBundle {
+    pub fn new(routes: Vec<BundledRoute>) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            routes,
+        }
+    }
+
+    pub fn parse(content: &str, format: BundleFormat) -> Result<Self> {
+        let bundle: RouteBundle = match format {
+            BundleFormat::Json => serde_json::from_str(content)?,
+            BundleFormat::Yaml => serde_yaml::from_str(content)?,
+        };
+
+        if bundle.version > BUNDLE_VERSION {
+            return Err(anyhow!(
+                "Unsupported route bundle version {} (supported: {})",
+                bundle.version,
+                BUNDLE_VERSION
+            ));
+        }
+        Ok(bundle)
+    }
+
+    pub fn render(&self, format: BundleFormat) -> Result<String> {
+        Ok(match format {
+            BundleFormat::Json => serde_json::to_string_pretty(self)?,
+            BundleFormat::Yaml => serde_yaml::to_string(self)?,
+        })
+    }
+}
+
+/// Walk a group's parent chain and return the group names from the root down.
+/// `parents` maps group id -> (parent id, name).
+pub fn group_chain(
+    parents: &HashMap<String, (Option<String>, String)>,
+    group_id: Option<&String>,
+) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut current = group_id;
+
+    while let Some(id) = current {
+        // Guard against a corrupted parent chain looping forever
+        if names.len() > parents.len() {
+            break;
+        }
+        match parents.get(id) {
+            Some((parent, name)) => {
+                names.push(name.clone());
+                current = parent.as_ref();
+            }
+            None => break,
+        }
+    }
+
+    names.reverse();
+    names
+}
+
+pub fn route_group_parents(
+    groups: &[route_groups::Model],
+) -> HashMap<String, (Option<String>, String)> {
+    groups
+        .iter()
+        .map(|g| (g.id.clone(), (g.parent_id.clone(), g.name.clone())))
+        .collect()
+}
+
+struct IndexedRepo {
+    model: repositories::Model,
+    tree_path: String,
+    normalized_remote: Option<String>,
+}
+
+impl IndexedRepo {
+    fn matches_path(&self, relative_path: &str) -> bool {
+        let wanted = relative_path.trim_matches('/').replace('\\', "/");
+        if wanted.is_empty() {
+            return false;
+        }
+        let local = self.model.local_path.replace('\\', "/");
+        self.tree_path == wanted
+            || local
+                .trim_end_matches('/')
+                .ends_with(&format!("/{}", wanted))
+    }
+}
+
+/// Lookup table translating between registered repositories and portable `RepoRef`s
+pub struct RepoIndex {
+    repos: Vec<IndexedRepo>,
+}
+
+impl RepoIndex {
+    pub fn new(groups: &[repo_groups::Model], repos: Vec<repositories::Model>) -> Self {
+        let parents: HashMap<String, (Option<String>, String)> = groups
+            .iter()
+            .map(|g| (g.id.clone(), (g.parent_id.clone(), g.name.clone())))
+            .collect();
+
+        let repos = repos
+            .into_iter()
+            .map(|model| {
+                let mut segments = group_chain(&parents, model.group_id.as_ref());
+                segments.push(model.name.clone());
+                IndexedRepo {
+                    tree_path: segments.join("/"),
+                    normalized_remote: model.remote_url.as_deref().map(normalize_remote_url),
+                    model,
+                }
+            })
+            .collect();
+
+        Self { repos }
+    }
+
+    pub fn reference(&self, repo_id: &str) -> Option<RepoRef> {
+        self.repos
+            .iter()
+            .find(|r| r.model.id == repo_id)
+            .map(|r| RepoRef {
+                remote_url: r.model.remote_url.clone(),
+                relative_path: Some(r.tree_path.clone()),
+            })
+    }
+
+    /// Resolve a reference by remote URL first, falling back to the relative path
+    pub fn resolve(&self, reference: &RepoRef) -> Option<&repositories::Model> {
+        if let Some(url) = &reference.remote_url {
+            let wanted = normalize_remote_url(url);
+            let candidates: Vec<&IndexedRepo> = self
+                .repos
+                .iter()
+                .filter(|r| r.normalized_remote.as_deref() == Some(wanted.as_str()))
+                .collect();
+
+            if let Some(first) = candidates.first() {
+                // The same remote may be checked out more than once; prefer the matching path
+                let preferred = reference
+                    .relative_path
+                    .as_deref()
+                    .and_then(|rel| candidates.iter().find(|r| r.matches_path(rel)));
+                return Some(&preferred.unwrap_or(first).model);
+            }
+        }
+
+        let relative_path = reference.relative_path.as_deref()?;
+        self.repos
+            .iter()
+            .find(|r| r.matches_path(relative_path))
+            .map(|r| &r.model)
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+
+/// Dependency graph between routes. An edge `route -> depends_on` means
+/// `depends_on` has to be synced before `route`.
+pub struct RouteGraph {
+    nodes: Vec<String>,
+    depends_on: HashMap<String, Vec<String>>,
+}
+
+impl RouteGraph {
+    /// Build a graph over `nodes`; edges touching routes outside `nodes` are ignored
+    pub fn new(nodes: Vec<String>, edges: &[(String, String)]) -> Self {
+        let members: HashSet<&String> = nodes.iter().collect();
+        let mut depends_on: HashMap<String, Vec<String>> = HashMap::new();
+
+        for (route, dependency) in edges {
+            if members.contains(route) && members.contains(dependency) {
+                depends_on
+                    .entry(route.clone())
+                    .or_default()
+                    .push(dependency.clone());
+            }
+        }
+
+        Self { nodes, depends_on }
+    }
+
+    pub fn dependencies_of(&self, route_id: &str) -> &[String] {
+        self.depends_on
+            .get(route_id)
+            .map(|d| d.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Order the routes so each one comes after everything it depends on.
+    /// Ties keep the input order. On a cycle, the routes forming it are returned as the error.
+    pub fn topological_order(&self) -> Result<Vec<String>, Vec<String>> {
+        let mut done: HashSet<&str> = HashSet::new();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while order.len() < self.nodes.len() {
+            let next = self.nodes.iter().find(|node| {
+                !done.contains(node.as_str())
+                    && self
+                        .dependencies_of(node)
+                        .iter()
+                        .all(|d| done.contains(d.as_str()))
+            });
+
+            match next {
+                Some(node) => {
+                    done.insert(node);
+                    order.push(node.clone());
+                }
+                None => return Err(self.find_cycle(&done)),
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Walk dependency edges from any unfinished node until a node repeats
+    fn find_cycle(&self, done: &HashSet<&str>) -> Vec<String> {
+        let Some(start) = self.nodes.iter().find(|n| !done.contains(n.as_str())) else {
+            return vec![];
+        };
+
+        let mut path: Vec<String> = vec![start.clone()];
+        loop {
+            let current = path.last().expect("path is never empty");
+            // Every unfinished node has at least one unfinished dependency
+            let Some(next) = self
+                .dependencies_of(current)
+                .iter()
+                .find(|d| !done.contains(d.as_str()))
+            else {
+                return path;
+            };
+
+            if let Some(pos) = path.iter().position(|p| p == next) {
+                return path.split_off(pos);
+            }
+            path.push(next.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(nodes: &[&str], edges: &[(&str, &str)]) -> RouteGraph {
+        RouteGraph::new(
+            nodes.iter().map(|n| n.to_string()).collect(),
+            &edges
+                .iter()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn dependencies_come_first_and_ties_keep_input_order() {
+        let graph = graph(&["a", "b", "c", "d"], &[("a", "c"), ("b", "d"), ("c", "d")]);
+        assert_eq!(graph.topological_order().unwrap(), ["d", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn edges_outside_the_nodes_are_ignored() {
+        let graph = graph(&["a", "b"], &[("a", "x"), ("y", "b")]);
+        assert!(graph.dependencies_of("a").is_empty());
+        assert_eq!(graph.topological_order().unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    fn cycle_reports_only_the_routes_forming_it() {
+        let graph = graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("b", "c"), ("c", "b"), ("d", "a")],
+        );
+        let mut cycle = graph.topological_order().unwrap_err();
+        cycle.sort();
+        assert_eq!(cycle, ["b", "c"]);
+    }
+}
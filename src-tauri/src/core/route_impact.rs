@@ -0,0 +1,115 @@
+use crate::commands::route::MappingRule;
+use crate::core::diff::DiffEngine;
+use crate::core::mapping_history::parse_mappings;
+use crate::core::repo_routes::RepoRouteRegistry;
+use crate::database::entities::routes;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+
+/// One route carrying a file from one repository into another
+#[derive(Serialize, Debug, Clone)]
+pub struct PathHop {
+    pub route_id: String,
+    pub route_name: String,
+    pub read_only: bool,
+    pub rule_index: usize,
+    pub rule: MappingRule,
+    pub source_repo_id: String,
+    pub source_path: String,
+    pub target_repo_id: String,
+    pub target_path: String,
+    /// 1 for routes reading the file directly, 2+ for routes picking up a copy of it
+    pub depth: usize,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct PathImpact {
+    pub hops: Vec<PathHop>,
+    /// Routes that could not be evaluated, e.g. because of invalid mappings
+    pub warnings: Vec<String>,
+}
+
+pub struct RouteImpactAnalyzer;
+
+impl RouteImpactAnalyzer {
+    /// Find every route that would carry `path` (relative to `repo_id`) and where it lands.
+    /// With `transitive`, copies landing in other repositories are followed further.
+    pub fn trace(
+        all_routes: &[routes::Model],
+        repo_id: &str,
+        path: &str,
+        transitive: bool,
+    ) -> PathImpact {
+        let mut impact = PathImpact::default();
+        let mut warned: HashSet<&str> = HashSet::new();
+        let mut visited: HashSet<(String, String)> = HashSet::new();
+        let mut queue: VecDeque<(String, String, usize)> = VecDeque::new();
+
+        let start = normalize_relative_path(path);
+        visited.insert((repo_id.to_string(), start.clone()));
+        queue.push_back((repo_id.to_string(), start, 1));
+
+        while let Some((current_repo, current_path, depth)) = queue.pop_front() {
+            for route in all_routes {
+                if route.main_repo_id.as_deref() != Some(current_repo.as_str()) {
+                    continue;
+                }
+                let Some(target_repo_id) = route.slave_repo_id.clone() else {
+                    continue;
+                };
+
+                let mapped = parse_mappings(route.mappings.as_deref()).and_then(|rules| {
+                    Ok(DiffEngine::map_path(&rules, &current_path)?
+                        .map(|(index, target)| (index, rules[index].clone(), target)))
+                });
+                let (rule_index, rule, target_path) = match mapped {
+                    Ok(Some(found)) => found,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        if warned.insert(route.id.as_str()) {
+                            impact
+                                .warnings
+                                .push(format!("Route '{}' skipped: {}", route.name, e));
+                        }
+                        continue;
+                    }
+                };
+
+                if transitive && visited.insert((target_repo_id.clone(), target_path.clone())) {
+                    queue.push_back((target_repo_id.clone(), target_path.clone(), depth + 1));
+                }
+
+                impact.hops.push(PathHop {
+                    route_id: route.id.clone(),
+                    route_name: route.name.clone(),
+                    read_only: RepoRouteRegistry::is_repo_defined(&route.id),
+                    rule_index,
+                    rule,
+                    source_repo_id: current_repo.clone(),
+                    source_path: current_path.clone(),
+                    target_repo_id,
+                    target_path,
+                    depth,
+                });
+            }
+        }
+
+        impact
+    }
+}
+
+/// Use forward slashes and drop leading `./` or `/` so paths compare like the sync walker's
+pub fn normalize_relative_path(path: &str) -> String {
+    let normalized = path.trim().replace('\\', "/");
+    let mut rest = normalized.as_str();
+    loop {
+        if let Some(stripped) = rest.strip_prefix("./") {
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix('/') {
+            rest = stripped;
+        } else {
+            break;
+        }
+    }
+    rest.to_string()
+}
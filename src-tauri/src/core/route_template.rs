@@ -0,0 +1,67 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// Placeholder substitution for route templates.
+/// Placeholders look like `{module}`; only identifier characters are allowed between the
+/// braces, so glob syntax such as `{a,b}` is left untouched.
+pub struct TemplateEngine;
+
+impl TemplateEngine {
+    /// List the distinct placeholder names used in `text`, in order of first appearance
+    pub fn variables(text: &str) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (_, name) in Self::placeholders(text) {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
+    /// Replace every placeholder in `text`; fails if a variable has no value
+    pub fn render(text: &str, values: &HashMap<String, String>) -> Result<String> {
+        let mut output = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for (start, name) in Self::placeholders(text) {
+            let value = values
+                .get(name)
+                .ok_or_else(|| anyhow!("Missing value for template variable '{}'", name))?;
+            output.push_str(&text[last..start]);
+            output.push_str(value);
+            last = start + name.len() + 2;
+        }
+        output.push_str(&text[last..]);
+
+        Ok(output)
+    }
+
+    /// Yield (byte offset of '{', placeholder name) pairs
+    fn placeholders(text: &str) -> Vec<(usize, &str)> {
+        let mut found = Vec::new();
+        let mut search_from = 0;
+
+        while let Some(rel) = text[search_from..].find('{') {
+            let start = search_from + rel;
+            let rest = &text[start + 1..];
+            match rest.find('}') {
+                Some(end) if Self::is_identifier(&rest[..end]) => {
+                    found.push((start, &rest[..end]));
+                    search_from = start + end + 2;
+                }
+                _ => search_from = start + 1,
+            }
+        }
+
+        found
+    }
+
+    fn is_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+            _ => return false,
+        }
+        chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }
+}
@@ -0,0 +1,354 @@
+use crate::commands::status::{check_repo_status, RepoStatus};
+use crate::core::git_history::parse_bound;
+use crate::core::periodic::{int_setting, PeriodicTask};
+use crate::database::entities::{repo_status_snapshots, repositories};
+use crate::database::manager::DatabaseManager;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::time::Duration;
+
+/// Minutes between status snapshots of every repository; 0 turns recording off
+pub const SNAPSHOT_INTERVAL_SETTING: &str = "status_snapshot_interval_minutes";
+/// Days snapshots are kept
+pub const SNAPSHOT_RETENTION_SETTING: &str = "status_snapshot_retention_days";
+
+const DEFAULT_INTERVAL_MINUTES: i64 = 30;
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+const DEFAULT_TIMELINE_LIMIT: u64 = 500;
+
+/// How long a repository has been in its current state, from the snapshots
+#[derive(Serialize, Debug, Clone)]
+pub struct StatusDuration {
+    pub repo_id: String,
+    pub status: String,
+    /// First snapshot of the current unbroken run of `status`
+    pub status_since: NaiveDateTime,
+    /// Set while the working tree has changes
+    pub dirty_since: Option<NaiveDateTime>,
+    /// Set while the branch is both ahead of and behind its upstream
+    pub diverged_since: Option<NaiveDateTime>,
+    pub last_snapshot_at: NaiveDateTime,
+}
+
+/// Periodically records the status of every repository so the dashboard can tell
+/// how long a repository has been dirty or diverged
+pub struct StatusRecorder {
+    db_manager: Arc<DatabaseManager>,
+    task: PeriodicTask,
+}
+
+impl StatusRecorder {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            db_manager,
+            task: PeriodicTask::new("StatusHistory"),
+        }
+    }
+
+    /// Start the background loop
+    pub async fn start(&self) {
+        let db_manager = self.db_manager.clone();
+        self.task
+            .start(Duration::ZERO, move || {
+                let db_manager = db_manager.clone();
+                async move { record_if_due(&db_manager).await }
+            })
+            .await;
+    }
+
+    #[allow(dead_code)]
+    pub async fn stop(&self) {
+        self.task.stop().await;
+    }
+}
+
+/// Snapshot every repository when the newest snapshot is older than the interval,
+/// then drop snapshots past the retention period
+async fn record_if_due(db_manager: &DatabaseManager) -> Result<()> {
+    let db = &db_manager.connection;
+    let interval = int_setting(db, SNAPSHOT_INTERVAL_SETTING)
+        .await?
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+    if interval <= 0 {
+        return Ok(());
+    }
+
+    let now = Utc::now().naive_utc();
+    let newest = repo_status_snapshots::Entity::find()
+        .order_by_desc(repo_status_snapshots::Column::TakenAt)
+        .one(db)
+        .await?;
+    if newest.is_some_and(|s| s.taken_at > now - chrono::Duration::minutes(interval)) {
+        return Ok(());
+    }
+
+    let repos = repositories::Entity::find().all(db).await?;
+    let statuses = tokio::task::spawn_blocking(move || {
+        repos.iter().map(check_repo_status).collect::<Vec<_>>()
+    })
+    .await?;
+    for status in &statuses {
+        snapshot(status, now).insert(db).await?;
+    }
+
+    let retention = int_setting(db, SNAPSHOT_RETENTION_SETTING)
+        .await?
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+        .max(1);
+    repo_status_snapshots::Entity::delete_many()
+        .filter(repo_status_snapshots::Column::TakenAt.lt(now - chrono::Duration::days(retention)))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+fn snapshot(status: &RepoStatus, taken_at: NaiveDateTime) -> repo_status_snapshots::ActiveModel {
+    let dirty = status.staged + status.unstaged + status.untracked + status.conflicted;
+    repo_status_snapshots::ActiveModel {
+        repo_id: Set(status.id.clone()),
+        taken_at: Set(taken_at),
+        status: Set(status.status.clone()),
+        branch: Set(status.branch.clone()),
+        head_sha: Set(status.head.clone()),
+        detached: Set(status.detached),
+        ahead: Set(status.ahead as i32),
+        behind: Set(status.behind as i32),
+        dirty_count: Set(dirty as i32),
+        operation: Set(status.operation.clone()),
+        ..Default::default()
+    }
+}
+
+/// Snapshots of one repository between `since` and `until` (RFC 3339 or YYYY-MM-DD),
+/// oldest first. Without bounds the most recent `limit` snapshots are returned.
+pub async fn timeline<C: ConnectionTrait>(
+    db: &C,
+    repo_id: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    limit: Option<u64>,
+) -> Result<Vec<repo_status_snapshots::Model>> {
+    let mut query = repo_status_snapshots::Entity::find()
+        .filter(repo_status_snapshots::Column::RepoId.eq(repo_id));
+    if let Some(since) = since.filter(|s| !s.trim().is_empty()) {
+        query = query.filter(repo_status_snapshots::Column::TakenAt.gte(bound(since, false)?));
+    }
+    if let Some(until) = until.filter(|s| !s.trim().is_empty()) {
+        query = query.filter(repo_status_snapshots::Column::TakenAt.lte(bound(until, true)?));
+    }
+
+    let mut snapshots = query
+        .order_by_desc(repo_status_snapshots::Column::TakenAt)
+        .limit(limit.unwrap_or(DEFAULT_TIMELINE_LIMIT))
+        .all(db)
+        .await?;
+    snapshots.reverse();
+    Ok(snapshots)
+}
+
+/// Current run of each repository's status, dirtiness and divergence
+pub async fn durations<C: ConnectionTrait>(db: &C) -> Result<Vec<StatusDuration>> {
+    let repo_ids: Vec<String> = repo_status_snapshots::Entity::find()
+        .select_only()
+        .column(repo_status_snapshots::Column::RepoId)
+        .distinct()
+        .order_by_asc(repo_status_snapshots::Column::RepoId)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut durations = Vec::with_capacity(repo_ids.len());
+    for repo_id in repo_ids {
+        let Some(latest) = repo_status_snapshots::Entity::find()
+            .filter(repo_status_snapshots::Column::RepoId.eq(&repo_id))
+            .order_by_desc(repo_status_snapshots::Column::TakenAt)
+            .one(db)
+            .await?
+        else {
+            continue;
+        };
+
+        let same_status =
+            Condition::all().add(repo_status_snapshots::Column::Status.eq(&latest.status));
+        let dirty = Condition::all().add(repo_status_snapshots::Column::DirtyCount.gt(0));
+        let diverged = Condition::all()
+            .add(repo_status_snapshots::Column::Ahead.gt(0))
+            .add(repo_status_snapshots::Column::Behind.gt(0));
+
+        durations.push(StatusDuration {
+            status_since: run_start(db, &repo_id, same_status)
+                .await?
+                .unwrap_or(latest.taken_at),
+            dirty_since: run_start(db, &repo_id, dirty).await?,
+            diverged_since: run_start(db, &repo_id, diverged).await?,
+            last_snapshot_at: latest.taken_at,
+            status: latest.status,
+            repo_id,
+        });
+    }
+    Ok(durations)
+}
+
+/// When the newest run of the repository's snapshots matching `holds` began;
+/// `None` if the newest snapshot does not match
+async fn run_start<C: ConnectionTrait>(
+    db: &C,
+    repo_id: &str,
+    holds: Condition,
+) -> Result<Option<NaiveDateTime>> {
+    // The run is everything after the newest snapshot that breaks it
+    let broken_at = repo_status_snapshots::Entity::find()
+        .filter(repo_status_snapshots::Column::RepoId.eq(repo_id))
+        .filter(holds.clone().not())
+        .order_by_desc(repo_status_snapshots::Column::TakenAt)
+        .one(db)
+        .await?
+        .map(|s| s.taken_at);
+
+    let mut query = repo_status_snapshots::Entity::find()
+        .filter(repo_status_snapshots::Column::RepoId.eq(repo_id))
+        .filter(holds);
+    if let Some(broken_at) = broken_at {
+        query = query.filter(repo_status_snapshots::Column::TakenAt.gt(broken_at));
+    }
+    Ok(query
+        .order_by_asc(repo_status_snapshots::Column::TakenAt)
+        .one(db)
+        .await?
+        .map(|s| s.taken_at))
+}
+
+fn bound(value: &str, end_of_day: bool) -> Result<NaiveDateTime> {
+    let seconds = parse_bound(value, end_of_day)?;
+    DateTime::<Utc>::from_timestamp(seconds, 0)
+        .map(|t| t.naive_utc())
+        .ok_or_else(|| anyhow!("Invalid date '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::entities::repo_groups;
+    use sea_orm::{Database, DatabaseConnection, Schema};
+
+    /// In-memory database with repositories "a" and "b"
+    async fn database() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+        for table in [
+            schema.create_table_from_entity(repo_groups::Entity),
+            schema.create_table_from_entity(repositories::Entity),
+            schema.create_table_from_entity(repo_status_snapshots::Entity),
+        ] {
+            db.execute(backend.build(&table)).await.unwrap();
+        }
+        for id in ["a", "b"] {
+            repositories::ActiveModel {
+                id: Set(id.to_string()),
+                name: Set(id.to_string()),
+                local_path: Set(format!("/repos/{}", id)),
+                auth_type: Set("none".to_string()),
+                pinned: Set(false),
+                auto_fetch: Set(false),
+                created_at: Set(at(0)),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+        db
+    }
+
+    fn at(minute: u32) -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, minute, 0)
+            .unwrap()
+    }
+
+    /// Store a snapshot of `repo_id` taken at minute `minute`
+    async fn record(db: &DatabaseConnection, repo_id: &str, minute: u32, status: &str, dirty: i32) {
+        let (ahead, behind) = if status == "Diverged" { (1, 1) } else { (0, 0) };
+        repo_status_snapshots::ActiveModel {
+            repo_id: Set(repo_id.to_string()),
+            taken_at: Set(at(minute)),
+            status: Set(status.to_string()),
+            ahead: Set(ahead),
+            behind: Set(behind),
+            dirty_count: Set(dirty),
+            detached: Set(false),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
+    fn dirty() -> Condition {
+        Condition::all().add(repo_status_snapshots::Column::DirtyCount.gt(0))
+    }
+
+    #[tokio::test]
+    async fn run_start_is_the_first_snapshot_after_the_last_break() {
+        let db = database().await;
+        record(&db, "a", 0, "Uncommitted", 1).await;
+        record(&db, "a", 10, "Clean", 0).await;
+        record(&db, "a", 20, "Uncommitted", 2).await;
+        record(&db, "a", 30, "Uncommitted", 3).await;
+        // Another repository's snapshots do not break the run
+        record(&db, "b", 25, "Clean", 0).await;
+
+        assert_eq!(run_start(&db, "a", dirty()).await.unwrap(), Some(at(20)));
+    }
+
+    #[tokio::test]
+    async fn run_start_without_a_break_is_the_oldest_snapshot() {
+        let db = database().await;
+        record(&db, "a", 0, "Uncommitted", 1).await;
+        record(&db, "a", 10, "Uncommitted", 1).await;
+
+        assert_eq!(run_start(&db, "a", dirty()).await.unwrap(), Some(at(0)));
+    }
+
+    #[tokio::test]
+    async fn run_start_is_none_when_the_newest_snapshot_breaks_the_run() {
+        let db = database().await;
+        record(&db, "a", 0, "Uncommitted", 1).await;
+        record(&db, "a", 10, "Clean", 0).await;
+
+        assert_eq!(run_start(&db, "a", dirty()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn durations_track_each_repository_separately() {
+        let db = database().await;
+        record(&db, "a", 0, "Clean", 0).await;
+        record(&db, "a", 10, "Diverged", 1).await;
+        record(&db, "a", 20, "Diverged", 0).await;
+        record(&db, "b", 0, "Uncommitted", 4).await;
+        record(&db, "b", 20, "Uncommitted", 5).await;
+
+        let durations = durations(&db).await.unwrap();
+        assert_eq!(durations.len(), 2);
+
+        let a = &durations[0];
+        assert_eq!((a.repo_id.as_str(), a.status.as_str()), ("a", "Diverged"));
+        assert_eq!(a.status_since, at(10));
+        assert_eq!(a.diverged_since, Some(at(10)));
+        assert_eq!(a.dirty_since, None);
+        assert_eq!(a.last_snapshot_at, at(20));
+
+        let b = &durations[1];
+        assert_eq!(b.status_since, at(0));
+        assert_eq!(b.dirty_since, Some(at(0)));
+        assert_eq!(b.diverged_since, None);
+    }
+}
@@ -0,0 +1,360 @@
+use crate::commands::status::{check_repo_status, RepoStatus};
+use crate::database::entities::repositories;
+use git2::Repository;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Duration, Instant};
+
+/// Quiet period before a changed repository is rescanned
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Longest a rescan waits while changes keep coming, e.g. during a build
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct Watched {
+    /// Repositories with an active watch, by id
+    repos: HashMap<String, repositories::Model>,
+    /// Paths watched for each repository
+    paths: HashMap<String, Vec<PathBuf>>,
+    statuses: HashMap<String, RepoStatus>,
+}
+
+/// Watches the working trees (and `.git` directories) of registered repositories.
+/// Changes are debounced, only the affected repository is rescanned, and the result is
+/// cached and pushed as a `repo_status_changed` event. Repositories that cannot be watched,
+/// e.g. once the system's watch limit is reached, are not cached and are scanned on demand.
+pub struct StatusWatcher {
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+    state: Arc<Mutex<Watched>>,
+}
+
+impl StatusWatcher {
+    /// Start the watcher; when the platform watcher is unavailable nothing is cached
+    /// and every status is computed on demand
+    pub fn start(app: AppHandle) -> Self {
+        let state = Arc::new(Mutex::new(Watched::default()));
+        let (tx, rx) = mpsc::unbounded_channel::<PathBuf>();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                if event.kind.is_access() {
+                    return;
+                }
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+        });
+
+        let watcher = Arc::new(Mutex::new(match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                eprintln!(
+                    "[StatusWatcher] Unavailable, statuses are not cached: {}",
+                    e
+                );
+                None
+            }
+        }));
+        if watcher.lock().unwrap().is_some() {
+            tauri::async_runtime::spawn(rescan_changed(rx, state.clone(), watcher.clone(), app));
+        }
+
+        Self { watcher, state }
+    }
+
+    /// Watch `repos` and forget repositories that are no longer registered
+    pub fn track(&self, repos: &[repositories::Model]) {
+        let mut watcher = self.watcher.lock().unwrap();
+        let Some(watcher) = watcher.as_mut() else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+
+        let ids: HashSet<&str> = repos.iter().map(|r| r.id.as_str()).collect();
+        let gone: Vec<String> = state
+            .repos
+            .keys()
+            .filter(|id| !ids.contains(id.as_str()))
+            .cloned()
+            .collect();
+        for id in gone {
+            state.repos.remove(&id);
+            unwatch_all(watcher, state.paths.remove(&id).unwrap_or_default());
+            state.statuses.remove(&id);
+        }
+
+        let mut limit_reached = false;
+        for repo in repos {
+            if let Some(known) = state.repos.get(&repo.id).cloned() {
+                if known == *repo {
+                    continue;
+                }
+                // Settings shown in the status changed, or the repository moved
+                state.statuses.remove(&repo.id);
+                if known.local_path == repo.local_path {
+                    state.repos.insert(repo.id.clone(), repo.clone());
+                    continue;
+                }
+                unwatch_all(watcher, state.paths.remove(&repo.id).unwrap_or_default());
+                state.repos.remove(&repo.id);
+            }
+            if limit_reached {
+                continue;
+            }
+
+            match watch_all(watcher, &watch_targets(Path::new(&repo.local_path))) {
+                Ok(paths) => {
+                    state.repos.insert(repo.id.clone(), repo.clone());
+                    state.paths.insert(repo.id.clone(), paths);
+                }
+                Err(e) => {
+                    limit_reached = matches!(e.kind, notify::ErrorKind::MaxFilesWatch);
+                    eprintln!(
+                        "[StatusWatcher] Cannot watch {}, its status is computed on demand: {}",
+                        repo.local_path, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Cached status of a watched repository
+    pub fn cached(&self, id: &str) -> Option<RepoStatus> {
+        self.state.lock().unwrap().statuses.get(id).cloned()
+    }
+
+    /// Cache a status computed outside the watcher; ignored for unwatched repositories
+    pub fn store(&self, status: &RepoStatus) {
+        let mut state = self.state.lock().unwrap();
+        if state.repos.contains_key(&status.id) {
+            state.statuses.insert(status.id.clone(), status.clone());
+        }
+    }
+}
+
+/// What to watch in a repository: the root and `.git` themselves, the branch refs, and each
+/// top-level directory git does not ignore. Watching the whole tree would spend the system's
+/// watches on ignored `node_modules`, `target` and the like. Directories created later are
+/// picked up by `rescan_changed` through the root's events.
+fn watch_targets(root: &Path) -> Vec<(PathBuf, RecursiveMode)> {
+    let git_dir = root.join(".git");
+    let mut targets = vec![
+        (root.to_path_buf(), RecursiveMode::NonRecursive),
+        (git_dir.clone(), RecursiveMode::NonRecursive),
+        (git_dir.join("refs"), RecursiveMode::Recursive),
+    ];
+    let git = Repository::open(root).ok();
+    if let Ok(entries) = std::fs::read_dir(root) {
+        for entry in entries.flatten() {
+            let name = PathBuf::from(entry.file_name());
+            if watched_directory(root, git.as_ref(), &name) {
+                targets.push((entry.path(), RecursiveMode::Recursive));
+            }
+        }
+    }
+    targets.retain(|(path, _)| path.exists());
+    targets
+}
+
+/// Whether `name`, directly below `root`, is a directory watched on its own
+fn watched_directory(root: &Path, git: Option<&Repository>, name: &Path) -> bool {
+    name != Path::new(".git")
+        && root.join(name).is_dir()
+        && !git.is_some_and(|git| git.is_path_ignored(name).unwrap_or(false))
+}
+
+/// Top-level directories among `relative_paths` that appeared or disappeared, with whether
+/// each one should be watched now
+fn top_level_changes(
+    repo: &repositories::Model,
+    relative_paths: &[PathBuf],
+) -> Vec<(PathBuf, bool)> {
+    let root = Path::new(&repo.local_path);
+    let git = Repository::open(root).ok();
+    let mut seen = HashSet::new();
+    relative_paths
+        .iter()
+        .filter(|path| path.components().count() == 1 && seen.insert(path.to_path_buf()))
+        .map(|name| (root.join(name), watched_directory(root, git.as_ref(), name)))
+        .collect()
+}
+
+/// Follow directories created in or removed from a repository's root. When a new watch
+/// fails, e.g. at the watch limit, the repository falls back to on-demand status.
+fn update_top_level_watches(
+    watcher: &Mutex<Option<RecommendedWatcher>>,
+    state: &Mutex<Watched>,
+    repo_id: &str,
+    changes: Vec<(PathBuf, bool)>,
+) {
+    let mut watcher = watcher.lock().unwrap();
+    let Some(watcher) = watcher.as_mut() else {
+        return;
+    };
+    let mut state = state.lock().unwrap();
+    if !state.repos.contains_key(repo_id) {
+        return;
+    }
+
+    for (path, watch) in changes {
+        let paths = state.paths.entry(repo_id.to_string()).or_default();
+        let known = paths.contains(&path);
+        if !watch {
+            if known {
+                let _ = watcher.unwatch(&path);
+                paths.retain(|p| *p != path);
+            }
+            continue;
+        }
+        // Watched again even when known: a directory removed and recreated lost its watch
+        match watcher.watch(&path, RecursiveMode::Recursive) {
+            Ok(()) => {
+                if !known {
+                    paths.push(path);
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "[StatusWatcher] Cannot watch {}, the repository's status is computed on demand: {}",
+                    path.display(),
+                    e
+                );
+                state.repos.remove(repo_id);
+                state.statuses.remove(repo_id);
+                unwatch_all(watcher, state.paths.remove(repo_id).unwrap_or_default());
+                return;
+            }
+        }
+    }
+}
+
+/// Watch every target, or none of them when one fails
+fn watch_all(
+    watcher: &mut RecommendedWatcher,
+    targets: &[(PathBuf, RecursiveMode)],
+) -> notify::Result<Vec<PathBuf>> {
+    let mut watched = Vec::new();
+    for (path, mode) in targets {
+        if let Err(e) = watcher.watch(path, *mode) {
+            unwatch_all(watcher, watched);
+            return Err(e);
+        }
+        watched.push(path.clone());
+    }
+    Ok(watched)
+}
+
+fn unwatch_all(watcher: &mut RecommendedWatcher, paths: Vec<PathBuf>) {
+    for path in paths {
+        let _ = watcher.unwatch(&path);
+    }
+}
+
+/// Collect changed paths until they settle, then rescan the repositories they belong to
+async fn rescan_changed(
+    mut rx: mpsc::UnboundedReceiver<PathBuf>,
+    state: Arc<Mutex<Watched>>,
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+    app: AppHandle,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut paths = vec![first];
+        let deadline = Instant::now() + MAX_DELAY;
+        loop {
+            let quiet = (Instant::now() + DEBOUNCE).min(deadline);
+            tokio::select! {
+                path = rx.recv() => match path {
+                    Some(path) => paths.push(path),
+                    None => break,
+                },
+                _ = sleep_until(quiet) => break,
+            }
+        }
+
+        let changed = {
+            let state = state.lock().unwrap();
+            changed_repositories(&state.repos, &paths)
+        };
+        for (repo, relative_paths) in changed {
+            let repo_id = repo.id.clone();
+            let scanned = tokio::task::spawn_blocking(move || {
+                let top_level = top_level_changes(&repo, &relative_paths);
+                let status =
+                    affects_status(&repo, &relative_paths).then(|| check_repo_status(&repo));
+                (top_level, status)
+            })
+            .await;
+            let Ok((top_level, status)) = scanned else {
+                continue;
+            };
+            if !top_level.is_empty() {
+                update_top_level_watches(&watcher, &state, &repo_id, top_level);
+            }
+            let Some(status) = status else {
+                continue;
+            };
+
+            let updated = {
+                let mut state = state.lock().unwrap();
+                if !state.repos.contains_key(&status.id) {
+                    continue;
+                }
+                state.statuses.insert(status.id.clone(), status.clone()) != Some(status.clone())
+            };
+            if updated {
+                let _ = app.emit("repo_status_changed", status);
+            }
+        }
+    }
+}
+
+/// Changed paths grouped by the repository containing them, relative to its root
+fn changed_repositories(
+    repos: &HashMap<String, repositories::Model>,
+    paths: &[PathBuf],
+) -> Vec<(repositories::Model, Vec<PathBuf>)> {
+    let mut changed: HashMap<&str, (&repositories::Model, Vec<PathBuf>)> = HashMap::new();
+    for path in paths {
+        // The innermost repository wins for nested repositories
+        let owner = repos
+            .values()
+            .filter_map(|repo| {
+                let relative = path.strip_prefix(&repo.local_path).ok()?;
+                Some((repo, relative))
+            })
+            .max_by_key(|(repo, _)| repo.local_path.len());
+        if let Some((repo, relative)) = owner {
+            changed
+                .entry(repo.id.as_str())
+                .or_insert_with(|| (repo, Vec::new()))
+                .1
+                .push(relative.to_path_buf());
+        }
+    }
+    changed
+        .into_values()
+        .map(|(repo, paths)| (repo.clone(), paths))
+        .collect()
+}
+
+/// Whether any change can alter the status: git's own bookkeeping (objects, logs, locks)
+/// and ignored files cannot
+fn affects_status(repo: &repositories::Model, relative_paths: &[PathBuf]) -> bool {
+    let git = Repository::open(&repo.local_path).ok();
+    relative_paths.iter().any(|path| {
+        if let Ok(inner) = path.strip_prefix(".git") {
+            let noise = inner.starts_with("objects")
+                || inner.starts_with("logs")
+                || inner.extension().is_some_and(|ext| ext == "lock");
+            return !noise;
+        }
+        match &git {
+            Some(git) => !git.is_path_ignored(path).unwrap_or(false),
+            None => true,
+        }
+    })
+}
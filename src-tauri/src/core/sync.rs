@@ -1,20 +1,32 @@
 use crate::commands::route::MappingRule;
 use crate::core::diff::{ChangeType, DiffEngine};
-use crate::database::entities::{repositories, routes};
+use crate::core::provenance::Provenance;
+use crate::core::repo_routes::get_route;
+use crate::database::entities::repositories;
 use crate::database::manager::DatabaseManager;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use git2::{ErrorCode, Index, Repository, Signature, StatusOptions};
 use sea_orm::EntityTrait;
 use std::fs;
 use std::path::Path;
 
 pub struct SyncEngine;
 
-#[allow(dead_code)]
 #[derive(Debug)]
 pub struct SyncResult {
     pub success_count: usize,
     pub fail_count: usize,
     pub logs: String,
+    pub route_id: String,
+    pub route_name: String,
+    pub source_repo_id: String,
+    /// Source HEAD at the time of the sync
+    pub source_head: Option<String>,
+    /// The source had uncommitted changes, so the copied files may not match `source_head`
+    pub source_dirty: bool,
+    pub target_path: String,
+    /// Target-relative paths of the files copied
+    pub synced_paths: Vec<String>,
 }
 
 impl SyncEngine {
@@ -22,10 +34,16 @@ impl SyncEngine {
         let db = &db_manager.connection;
 
         // 1. Fetch Route
-        let route = routes::Entity::find_by_id(route_id)
-            .one(db)
-            .await?
-            .ok_or(anyhow::anyhow!("Route not found"))?;
+        let route = get_route(db, route_id).await?;
+        if route.archived_at.is_some() {
+            return Err(anyhow::anyhow!(
+                "Route is archived: {}",
+                route
+                    .archived_reason
+                    .as_deref()
+                    .unwrap_or("repository removed")
+            ));
+        }
 
         // 2. Fetch Repos
         let source_repo = repositories::Entity::find_by_id(
@@ -57,7 +75,15 @@ impl SyncEngine {
         // 4. Scan
         let summary = DiffEngine::scan_changes_with_roots(source_root, target_root, &mappings)?;
 
+        let source_git = Repository::open(source_root).ok();
+        let source_head = source_git
+            .as_ref()
+            .and_then(|repo| repo.head().ok()?.target())
+            .map(|oid| oid.to_string());
+        let source_dirty = source_git.as_ref().is_none_or(is_dirty);
+
         // 5. Execute
+        let mut synced_paths = Vec::new();
         let mut success = 0;
         let mut fail = 0;
         let mut logs = String::new();
@@ -95,6 +121,10 @@ impl SyncEngine {
                                     "[OK] Copied {:?} -> {:?}\n",
                                     src_path, dst_path
                                 ));
+                                if let Ok(relative) = dst_path.strip_prefix(target_root) {
+                                    synced_paths
+                                        .push(relative.to_string_lossy().replace('\\', "/"));
+                                }
                                 success += 1;
                             }
                             Err(e) => {
@@ -133,6 +163,91 @@ impl SyncEngine {
             success_count: success,
             fail_count: fail,
             logs,
+            route_id: route.id,
+            route_name: route.name,
+            source_repo_id: source_repo.id,
+            source_head,
+            source_dirty,
+            target_path: target_repo.local_path,
+            synced_paths,
         })
     }
+
+    /// Commit the files a sync copied into the target, with provenance trailers naming the
+    /// route and the source HEAD. The commit is HEAD plus the synced files only; anything the
+    /// user had staged stays staged. The source commit is left out when the source was dirty.
+    /// Returns the new commit, or `None` when nothing changed.
+    pub fn commit(
+        result: &SyncResult,
+        message: Option<&str>,
+        signature: &Signature,
+    ) -> Result<Option<String>> {
+        if result.synced_paths.is_empty() {
+            return Ok(None);
+        }
+        let repo = Repository::open(&result.target_path)?;
+        let parent = match repo.head().and_then(|head| head.peel_to_commit()) {
+            Ok(commit) => Some(commit),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut index = repo.index()?;
+        let mut tree_index = Index::new()?;
+        if let Some(parent) = &parent {
+            tree_index.read_tree(&parent.tree()?)?;
+        }
+        for path in &result.synced_paths {
+            index.add_path(Path::new(path))?;
+            let entry = index
+                .get_path(Path::new(path), 0)
+                .ok_or_else(|| anyhow!("{} is missing from the index", path))?;
+            tree_index.add(&entry)?;
+        }
+
+        let tree_id = tree_index.write_tree_to(&repo)?;
+        let unchanged = match &parent {
+            Some(parent) => parent.tree_id() == tree_id,
+            None => repo.find_tree(tree_id)?.is_empty(),
+        };
+        if unchanged {
+            return Ok(None);
+        }
+
+        let message = message
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| format!("Sync {}", result.route_name));
+        let source_commits = match &result.source_head {
+            Some(head) if !result.source_dirty => vec![head.clone()],
+            _ => Vec::new(),
+        };
+        let provenance = Provenance {
+            route_id: result.route_id.clone(),
+            source_repo_id: Some(result.source_repo_id.clone()),
+            source_commits,
+        };
+        let tree = repo.find_tree(tree_id)?;
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let oid = repo.commit(
+            Some("HEAD"),
+            signature,
+            signature,
+            provenance.append_to(&message).trim(),
+            &tree,
+            &parents,
+        )?;
+        // The synced files are committed now; the rest of the index is left as it was
+        index.write()?;
+        Ok(Some(oid.to_string()))
+    }
+}
+
+/// Uncommitted or untracked (not ignored) changes in the working tree; unreadable counts as dirty
+fn is_dirty(repo: &Repository) -> bool {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true);
+    repo.statuses(Some(&mut opts))
+        .map_or(true, |statuses| !statuses.is_empty())
 }
@@ -0,0 +1,66 @@
+use crate::database::entities::repositories;
+use git2::{Repository, Signature};
+use std::path::PathBuf;
+
+/// Scratch directory removed when dropped
+pub(crate) struct Root(pub(crate) PathBuf);
+
+impl Root {
+    pub(crate) fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("crate-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Create a checkout at `name` with `remote` as origin and return its path
+    pub(crate) fn checkout(&self, name: &str, remote: Option<&str>) -> String {
+        let repo = Repository::init(self.0.join(name)).unwrap();
+        if let Some(remote) = remote {
+            repo.remote("origin", remote).unwrap();
+        }
+        self.path(name)
+    }
+
+    /// Give the checkout at `name` an empty root commit and return its sha
+    pub(crate) fn root_commit(&self, name: &str, message: &str) -> String {
+        let repo = Repository::open(self.0.join(name)).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[])
+            .unwrap()
+            .to_string()
+    }
+
+    pub(crate) fn path(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().to_string()
+    }
+}
+
+impl Drop for Root {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A registered repository `id` at `path` without credentials
+pub(crate) fn repository(id: &str, path: &str, remote: Option<&str>) -> repositories::Model {
+    repositories::Model {
+        id: id.to_string(),
+        group_id: None,
+        name: id.to_string(),
+        local_path: path.to_string(),
+        remote_url: remote.map(String::from),
+        branch: None,
+        auth_type: "none".to_string(),
+        auth_username: None,
+        auth_secret: None,
+        auth_key_path: None,
+        pinned: false,
+        auto_fetch: false,
+        root_commit: None,
+        missing_since: None,
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+}
@@ -1,6 +1,10 @@
 pub mod repo_groups;
+pub mod repo_status_snapshots;
 pub mod repositories;
+pub mod route_dependencies;
 pub mod route_groups;
+pub mod route_mapping_revisions;
+pub mod route_templates;
 pub mod routes;
 pub mod settings;
 pub mod task_execution_logs;
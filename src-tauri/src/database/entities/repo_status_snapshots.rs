@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "repo_status_snapshots")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub repo_id: String,
+    pub taken_at: DateTime,
+    pub status: String, // Same values as RepoStatus.status
+    pub branch: Option<String>,
+    pub head_sha: Option<String>,
+    pub detached: bool,
+    pub ahead: i32,
+    pub behind: i32,
+    /// Staged, unstaged, untracked and conflicted entries
+    pub dirty_count: i32,
+    pub operation: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::repositories::Entity",
+        from = "Column::RepoId",
+        to = "super::repositories::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Repository,
+}
+
+impl Related<super::repositories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Repository.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
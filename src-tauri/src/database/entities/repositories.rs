@@ -13,9 +13,20 @@ pub struct Model {
     pub branch: Option<String>,
     pub auth_type: String, // 'none', 'ssh', 'token'
     pub auth_username: Option<String>,
+    /// Encrypted by `CredentialVault`; never sent to the frontend
+    #[serde(skip_serializing)]
     pub auth_secret: Option<String>,
+    /// Private key for `ssh` auth; the SSH agent is used when empty
+    pub auth_key_path: Option<String>,
     #[sea_orm(default_value = false)]
     pub pinned: bool,
+    /// Fetch in the background every `fetch_interval_minutes`
+    #[sea_orm(default_value = false)]
+    pub auto_fetch: bool,
+    /// Oldest parentless commit reachable from HEAD; identifies the checkout after a move
+    pub root_commit: Option<String>,
+    /// Set when a rescan found the checkout gone; cleared once it is back
+    pub missing_since: Option<DateTime>,
     pub created_at: DateTime,
 }
 
@@ -29,10 +40,6 @@ pub enum Relation {
         on_delete = "SetNull"
     )]
     RepoGroup,
-    // #[sea_orm(has_many = "super::routes::Entity")]
-    // RoutesMain,
-    // #[sea_orm(has_many = "super::routes::Entity")]
-    // RoutesSlave,
 }
 
 impl Related<super::repo_groups::Entity> for Entity {
@@ -41,16 +48,31 @@ impl Related<super::repo_groups::Entity> for Entity {
     }
 }
 
-// Routes has two relations to Repositories (MainRepo, SlaveRepo).
-// This introduces ambiguity for `Related<routes::Entity>`.
-// We need to define manual implementation or drop reverse relation if not strictly needed for this migration task.
-// Or we can use `Linked` trait for more complex relations.
-// For now, let's remove the reverse relation from Repository to Routes to fix compilation, as we can query Routes by repo_id directly.
-
-// impl Related<super::routes::Entity> for Entity {
-//     fn to() -> RelationDef {
-//         super::routes::Relation::MainRepo.def()
-//     }
-// }
+// Routes has two relations to Repositories (MainRepo, SlaveRepo), so a single
+// `Related<routes::Entity>` would be ambiguous. Each direction is a `Linked` instead.
+
+/// Routes that read from this repository
+pub struct RoutesAsSource;
+
+impl Linked for RoutesAsSource {
+    type FromEntity = Entity;
+    type ToEntity = super::routes::Entity;
+
+    fn link(&self) -> Vec<RelationDef> {
+        vec![super::routes::Relation::MainRepo.def().rev()]
+    }
+}
+
+/// Routes that write into this repository
+pub struct RoutesAsTarget;
+
+impl Linked for RoutesAsTarget {
+    type FromEntity = Entity;
+    type ToEntity = super::routes::Entity;
+
+    fn link(&self) -> Vec<RelationDef> {
+        vec![super::routes::Relation::SlaveRepo.def().rev()]
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
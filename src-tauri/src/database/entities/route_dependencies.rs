@@ -0,0 +1,33 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "route_dependencies")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub route_id: String,            // the dependent route
+    pub depends_on_route_id: String, // must be synced before `route_id`
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::routes::Entity",
+        from = "Column::RouteId",
+        to = "super::routes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Route,
+    #[sea_orm(
+        belongs_to = "super::routes::Entity",
+        from = "Column::DependsOnRouteId",
+        to = "super::routes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    DependsOn,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
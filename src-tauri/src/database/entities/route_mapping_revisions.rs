@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "route_mapping_revisions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub route_id: String,
+    pub created_at: DateTime,
+    pub previous_mappings: Option<String>, // JSON stored as string
+    pub new_mappings: Option<String>,      // JSON stored as string
+    pub diff: Option<String>,              // JSON MappingDiff between previous and new
+    pub note: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::routes::Entity",
+        from = "Column::RouteId",
+        to = "super::routes::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Route,
+}
+
+impl Related<super::routes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Route.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "route_templates")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub route_name: String, // may contain placeholders, e.g. "{module} components"
+    pub route_description: Option<String>,
+    pub main_repo_id: Option<String>,
+    pub slave_repo_id: Option<String>,
+    pub mappings: Option<String>, // JSON stored as string, may contain placeholders
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::repositories::Entity",
+        from = "Column::MainRepoId",
+        to = "super::repositories::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    MainRepo,
+    #[sea_orm(
+        belongs_to = "super::repositories::Entity",
+        from = "Column::SlaveRepoId",
+        to = "super::repositories::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    SlaveRepo,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -15,6 +15,9 @@ pub struct Model {
     pub last_sync_time: Option<DateTime>,
     pub updated_at: DateTime,
     pub mappings: Option<String>, // JSON stored as string
+    /// Set when the route lost a repository and was kept instead of deleted
+    pub archived_at: Option<DateTime>,
+    pub archived_reason: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -56,34 +56,59 @@ impl DatabaseManager {
 
         create_table(db, &schema, repo_groups::Entity).await?;
         create_table(db, &schema, repositories::Entity).await?;
+        create_table(db, &schema, repo_status_snapshots::Entity).await?;
         create_table(db, &schema, route_groups::Entity).await?;
         create_table(db, &schema, routes::Entity).await?;
+        create_table(db, &schema, route_templates::Entity).await?;
+        create_table(db, &schema, route_mapping_revisions::Entity).await?;
+        create_table(db, &schema, route_dependencies::Entity).await?;
         create_table(db, &schema, tasks::Entity).await?;
         create_table(db, &schema, task_steps::Entity).await?;
         create_table(db, &schema, task_execution_logs::Entity).await?;
         create_table(db, &schema, settings::Entity).await?;
         create_table(db, &schema, workspace_config::Entity).await?;
 
+        // Status durations look up each repository's snapshots by time
+        let index = sea_orm::sea_query::Index::create()
+            .if_not_exists()
+            .name("idx_repo_status_snapshots_repo_taken_at")
+            .table(repo_status_snapshots::Entity)
+            .col(repo_status_snapshots::Column::RepoId)
+            .col(repo_status_snapshots::Column::TakenAt)
+            .to_owned();
+        db.execute(backend.build(&index)).await?;
+
         Ok(())
     }
 
     async fn run_manual_migrations(db: &DatabaseConnection) -> Result<(), Box<dyn std::error::Error>> {
-        // Migration 1: Add 'pinned' column to 'repositories' table
         let backend = db.get_database_backend();
-        let sql = match backend {
-            sea_orm::DbBackend::Sqlite => "ALTER TABLE repositories ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT 0",
-            _ => return Ok(()), // Only supporting SQLite for now as per tech stack
-        };
+        if backend != sea_orm::DbBackend::Sqlite {
+            return Ok(()); // Only supporting SQLite for now as per tech stack
+        }
+
+        // Columns added after the first release; tables created by `create_tables_if_not_exist`
+        // on a fresh install already have them.
+        let migrations = [
+            ("pinned column", "ALTER TABLE repositories ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT 0"),
+            ("archived_at column", "ALTER TABLE routes ADD COLUMN archived_at TEXT NULL"),
+            ("archived_reason column", "ALTER TABLE routes ADD COLUMN archived_reason TEXT NULL"),
+            ("auth_key_path column", "ALTER TABLE repositories ADD COLUMN auth_key_path TEXT NULL"),
+            ("auto_fetch column", "ALTER TABLE repositories ADD COLUMN auto_fetch BOOLEAN NOT NULL DEFAULT 0"),
+            ("root_commit column", "ALTER TABLE repositories ADD COLUMN root_commit TEXT NULL"),
+            ("missing_since column", "ALTER TABLE repositories ADD COLUMN missing_since TEXT NULL"),
+        ];
 
         // We try to execute. If column exists, it will fail, which is fine for this simple migration strategy.
         // For production, we should check if column exists or use schema versioning.
         // For this local-first app, ignoring error "duplicate column name" is a quick hack.
-
-        match db.execute(Statement::from_string(backend, sql.to_string())).await {
-            Ok(_) => println!("Applied migration: Added pinned column"),
-            Err(e) => {
-                if !e.to_string().contains("duplicate column name") {
-                    eprintln!("Migration warning (might be safe if column exists): {}", e);
+        for (name, sql) in migrations {
+            match db.execute(Statement::from_string(backend, sql.to_string())).await {
+                Ok(_) => println!("Applied migration: Added {}", name),
+                Err(e) => {
+                    if !e.to_string().contains("duplicate column name") {
+                        eprintln!("Migration warning (might be safe if column exists): {}", e);
+                    }
                 }
             }
         }
@@ -25,14 +25,29 @@ pub fn run() {
             commands::settings::get_available_ides,
             commands::repo::create_repo_group,
             commands::repo::delete_repo_group,
+            commands::repo::preview_repo_group_deletion,
             commands::repo::update_repo_group,
             commands::repo::add_repository,
             commands::repo::update_repository,
             commands::repo::delete_repository,
+            commands::repo::preview_repository_deletion,
             commands::repo::update_repository_group,
             commands::repo::update_repo_group_parent,
             commands::repo::list_repo_tree,
             commands::repo::toggle_pin_repo,
+            commands::repo::set_repo_auto_fetch,
+            commands::repo_clone::clone_repository,
+            commands::repo_health::check_repo_health,
+            commands::repo_health::check_group_health,
+            commands::repo_relocate::find_relocation_candidates,
+            commands::repo_relocate::relocate_repositories,
+            commands::repo_reconcile::reconcile_scan,
+            commands::repo_reconcile::apply_reconcile,
+            commands::credentials::get_repository_credentials,
+            commands::credentials::set_repository_credentials,
+            commands::credentials::get_credential_vault_status,
+            commands::credentials::unlock_credentials,
+            commands::credentials::set_credential_passphrase,
             commands::route::create_route_group,
             commands::route::delete_route_group,
             commands::route::update_route_group,
@@ -40,6 +55,7 @@ pub fn run() {
             commands::route::update_route,
             commands::route::update_route_mappings,
             commands::route::delete_route,
+            commands::route::clone_route,
             commands::route::update_route_group_id,
             commands::route::update_route_group_parent,
             commands::route::list_route_tree,
@@ -47,6 +63,23 @@ pub fn run() {
             commands::route::test_route_mapping,
             commands::route::preview_glob_matches,
             commands::route::sync_route,
+            commands::route_bundle::export_routes,
+            commands::route_bundle::import_routes,
+            commands::route_graph::set_route_dependencies,
+            commands::route_graph::get_route_dependencies,
+            commands::route_graph::sync_route_group,
+            commands::route_graph::get_route_graph,
+            commands::route_history::list_route_revisions,
+            commands::route_history::compare_route_revisions,
+            commands::route_history::restore_route_revision,
+            commands::route_impact::list_routes_by_repository,
+            commands::route_impact::analyze_path_impact,
+            commands::route_template::list_route_templates,
+            commands::route_template::create_route_template,
+            commands::route_template::create_template_from_route,
+            commands::route_template::update_route_template,
+            commands::route_template::delete_route_template,
+            commands::route_template::instantiate_route_template,
             commands::task::create_task,
             commands::task::update_task,
             commands::task::run_task_now,
@@ -60,15 +93,47 @@ pub fn run() {
             commands::repo_scan::import_scanned_repos,
             commands::git_ops::get_git_branches,
             commands::git_ops::switch_git_branch,
+            commands::git_ops::list_git_stashes,
+            commands::git_ops::stash_git_changes,
+            commands::git_ops::apply_git_stash,
+            commands::git_ops::pop_git_stash,
+            commands::git_ops::drop_git_stash,
             commands::git_ops::get_git_log,
+            commands::git_ops::query_git_history,
+            commands::git_ops::get_commit_detail,
             commands::git_ops::open_in_folder,
             commands::git_ops::open_in_terminal,
             commands::git_ops::open_in_ide,
+            commands::git_branches::create_git_branch,
+            commands::git_branches::create_tracking_branch,
+            commands::git_branches::rename_git_branch,
+            commands::git_branches::delete_git_branch,
+            commands::git_branches::set_branch_upstream,
+            commands::git_branches::compare_git_branches,
+            commands::git_changes::get_working_tree_changes,
+            commands::git_changes::stage_git_paths,
+            commands::git_changes::unstage_git_paths,
+            commands::git_changes::get_file_hunks,
+            commands::git_changes::stage_git_hunks,
+            commands::git_changes::unstage_git_hunks,
+            commands::git_changes::discard_git_changes,
+            commands::git_changes::commit_git_changes,
+            commands::git_tags::list_git_tags,
+            commands::git_tags::create_git_tag,
+            commands::git_tags::delete_git_tag,
+            commands::git_tags::push_git_tags,
+            commands::git_tags::tag_repo_group,
+            commands::commit_port::port_commits,
+            commands::provenance::trace_provenance,
+            commands::git_bulk::bulk_git_operation,
+            commands::git_remote::run_git_operation,
             commands::terminal::run_shell_command,
             commands::workspace::get_workspace_config,
             commands::workspace::save_workspace_config,
             commands::workspace::launch_workspace,
             commands::status::get_repos_status,
+            commands::status::get_repo_status_timeline,
+            commands::status::get_repo_status_durations,
             commands::status::scan_dependencies,
             commands::ai::generate_ai_response,
             commands::ai::generate_release_notes,
@@ -119,10 +184,18 @@ pub fn run() {
                     .path()
                     .app_data_dir()
                     .expect("failed to get app data dir");
-                let db_manager = database::manager::DatabaseManager::new(app_data_dir)
+                let db_manager = database::manager::DatabaseManager::new(app_data_dir.clone())
                     .await
                     .expect("failed to initialize database");
 
+                // Load the credential key and encrypt any plaintext secrets
+                if let Err(e) =
+                    core::credentials::CredentialVault::init(&app_data_dir, &db_manager.connection)
+                        .await
+                {
+                    eprintln!("[Setup] Credential vault unavailable: {}", e);
+                }
+
                 // Init defaults
                 commands::settings::init_defaults(&db_manager)
                     .await
@@ -130,13 +203,36 @@ pub fn run() {
 
                 // Start scheduler
                 let db_manager_arc = std::sync::Arc::new(db_manager.clone());
-                let scheduler = core::scheduler::SchedulerManager::new(db_manager_arc);
+                let scheduler = core::scheduler::SchedulerManager::new(db_manager_arc.clone());
                 scheduler.start().await;
 
+                // Keep ahead/behind current for repositories with auto fetch
+                core::background_fetch::BackgroundFetcher::new(db_manager_arc.clone())
+                    .start()
+                    .await;
+
+                // Record repository status over time for the dashboard
+                core::status_history::StatusRecorder::new(db_manager_arc)
+                    .start()
+                    .await;
+
+                // Fill in root commits for repositories registered before they were recorded
+                let backfill_db = db_manager.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) =
+                        core::relocate::backfill_root_commits(&backfill_db.connection).await
+                    {
+                        eprintln!("[Setup] Root commit backfill failed: {}", e);
+                    }
+                });
+
                 // Keep scheduler alive by managing it (optional: store in state if needed)
                 // For now, it runs as a background task
 
                 app_handle.manage(db_manager);
+                app_handle.manage(core::status_watcher::StatusWatcher::start(
+                    app_handle.clone(),
+                ));
             });
 
             Ok(())
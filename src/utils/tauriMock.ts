@@ -41,9 +41,69 @@ const mockStorage: Record<string, any> = {
     { id: 'route-group-1', name: '同步路线组', parent_id: null, sort_order: 0 },
   ],
   routes: [],
-  tasks: []
+  tasks: [],
+  credentials: {} as Record<string, { auth_type: string; username: string | null; key_path: string | null; secret: string | null }>,
+  vault: { mode: 'key_file', unlocked: true, passphrase: null as string | null },
 };
 
+// Helpers mirroring the backend's deletion impact analysis (routes only)
+function descendantRepoGroupIds(rootId: string): string[] {
+  const ids = [rootId];
+  for (let i = 0; i < ids.length; i++) {
+    ids.push(...mockStorage.repoGroups.filter((g: any) => g.parent_id === ids[i]).map((g: any) => g.id));
+  }
+  return ids;
+}
+
+function repoDeletionImpact(repoIds: string[], groupIds: string[] = [], keptRepoIds: string[] = []) {
+  const named = (items: any[]) => items.map((i: any) => ({ id: i.id, name: i.name }));
+  return {
+    repositories: named(mockStorage.repositories.filter((r: any) => repoIds.includes(r.id))),
+    groups: named(mockStorage.repoGroups.filter((g: any) => groupIds.includes(g.id))),
+    ungrouped_repositories: named(mockStorage.repositories.filter((r: any) => keptRepoIds.includes(r.id))),
+    routes: mockStorage.routes
+      .filter((r: any) => repoIds.includes(r.source_id) || repoIds.includes(r.target_id))
+      .map((r: any) => {
+        const source = repoIds.includes(r.source_id);
+        const target = repoIds.includes(r.target_id);
+        return { id: r.id, name: r.name, source_id: r.source_id, target_id: r.target_id, role: source && target ? 'both' : source ? 'source' : 'target' };
+      }),
+    task_steps: [],
+    workspace_configs: [],
+  };
+}
+
+function groupDeletionImpact(id: string, deleteRepositories: boolean) {
+  const groupIds = descendantRepoGroupIds(id);
+  const repoIds = mockStorage.repositories.filter((r: any) => groupIds.includes(r.group_id)).map((r: any) => r.id);
+  return deleteRepositories ? repoDeletionImpact(repoIds, groupIds) : repoDeletionImpact([], groupIds, repoIds);
+}
+
+// Remove repositories and apply the chosen route action, or fail like the backend without `confirm`
+function deleteRepositoriesWithImpact(impact: any, { confirm, route_action, reassign_to }: any) {
+  if (impact.routes.length > 0 && !confirm) {
+    throw `Deleting affects ${impact.routes.length} route(s), 0 task step(s) and 0 workspace configuration(s); confirm to continue`;
+  }
+  const repoIds = impact.repositories.map((r: any) => r.id);
+  const action = route_action || 'delete';
+  if (action === 'reassign' && !reassign_to) {
+    throw 'reassign_to is required to reassign routes';
+  }
+  mockStorage.routes = mockStorage.routes.filter((route: any) => {
+    const affected = repoIds.includes(route.source_id) || repoIds.includes(route.target_id);
+    if (!affected) return true;
+    if (action === 'delete') return false;
+    for (const key of ['source_id', 'target_id']) {
+      if (repoIds.includes(route[key])) {
+        route[key] = action === 'reassign' ? reassign_to : null;
+      }
+    }
+    if (action === 'archive') route.archived = true;
+    return true;
+  });
+  mockStorage.repositories = mockStorage.repositories.filter((r: any) => !repoIds.includes(r.id));
+}
+
 // Helper to build tree from flat data
 function buildRepoTree(): any[] {
   const groups = mockStorage.repoGroups;
@@ -145,6 +205,42 @@ const mockCommands: Record<string, (args: any) => Promise<any>> = {
   get_all_settings: async () => {
     return mockStorage.settings;
   },
+
+  // Credentials
+  get_repository_credentials: async ({ repo_id }: { repo_id: string }) => {
+    const creds = mockStorage.credentials[repo_id];
+    return {
+      auth_type: creds?.auth_type ?? 'none',
+      username: creds?.username ?? null,
+      key_path: creds?.key_path ?? null,
+      has_secret: !!creds?.secret,
+    };
+  },
+  set_repository_credentials: async (args: { repo_id: string; auth_type: string; username?: string | null; secret?: string | null; key_path?: string | null }) => {
+    if (!['none', 'ssh', 'token'].includes(args.auth_type)) throw `Unknown auth type: ${args.auth_type}`;
+    const current = mockStorage.credentials[args.repo_id];
+    if (args.secret && !mockStorage.vault.unlocked) throw 'Credentials are locked';
+    mockStorage.credentials[args.repo_id] = {
+      auth_type: args.auth_type,
+      username: args.username || null,
+      key_path: args.key_path || null,
+      // null keeps the stored secret, an empty string removes it
+      secret: args.secret == null ? current?.secret ?? null : args.secret || null,
+    };
+  },
+  get_credential_vault_status: async () => {
+    return { mode: mockStorage.vault.mode, unlocked: mockStorage.vault.unlocked };
+  },
+  unlock_credentials: async ({ passphrase }: { passphrase: string }) => {
+    if (mockStorage.vault.mode !== 'passphrase') throw 'No credential passphrase is configured';
+    if (passphrase !== mockStorage.vault.passphrase) throw 'Wrong passphrase';
+    mockStorage.vault.unlocked = true;
+  },
+  set_credential_passphrase: async ({ passphrase }: { passphrase?: string | null }) => {
+    if (!mockStorage.vault.unlocked) throw 'Unlock credentials before changing protection';
+    mockStorage.vault.mode = passphrase ? 'passphrase' : 'key_file';
+    mockStorage.vault.passphrase = passphrase || null;
+  },
   
   // Repo Groups
   create_repo_group: async ({ name, parent_id }: { name: string; parent_id?: string }) => {
@@ -152,12 +248,18 @@ const mockCommands: Record<string, (args: any) => Promise<any>> = {
     mockStorage.repoGroups.push({ id, name, parent_id: parent_id || null, sort_order: 0 });
     console.log('[Mock] Created repo group:', name, 'parent:', parent_id);
   },
-  delete_repo_group: async ({ id }: { id: string }) => {
-    const index = mockStorage.repoGroups.findIndex((g: any) => g.id === id);
-    if (index !== -1) {
-      mockStorage.repoGroups.splice(index, 1);
-      console.log('[Mock] Deleted repo group:', id);
-    }
+  preview_repo_group_deletion: async ({ id, delete_repositories }: { id: string; delete_repositories?: boolean }) => {
+    return groupDeletionImpact(id, !!delete_repositories);
+  },
+  delete_repo_group: async (args: { id: string; delete_repositories?: boolean; confirm?: boolean; route_action?: string; reassign_to?: string }) => {
+    const impact = groupDeletionImpact(args.id, !!args.delete_repositories);
+    deleteRepositoriesWithImpact(impact, args);
+    const groupIds = impact.groups.map((g: any) => g.id);
+    mockStorage.repositories.forEach((r: any) => {
+      if (groupIds.includes(r.group_id)) r.group_id = null;
+    });
+    mockStorage.repoGroups = mockStorage.repoGroups.filter((g: any) => !groupIds.includes(g.id));
+    console.log('[Mock] Deleted repo group:', args.id);
   },
   update_repo_group_parent: async ({ id, parent_id }: { id: string; parent_id?: string }) => {
     const group = mockStorage.repoGroups.find((g: any) => g.id === id);
@@ -189,12 +291,12 @@ const mockCommands: Record<string, (args: any) => Promise<any>> = {
       console.log('[Mock] Moved repository:', id, 'to group:', group_id);
     }
   },
-  delete_repository: async ({ id }: { id: string }) => {
-    const index = mockStorage.repositories.findIndex((r: any) => r.id === id);
-    if (index !== -1) {
-      mockStorage.repositories.splice(index, 1);
-      console.log('[Mock] Deleted repository:', id);
-    }
+  preview_repository_deletion: async ({ id }: { id: string }) => {
+    return repoDeletionImpact([id]);
+  },
+  delete_repository: async (args: { id: string; confirm?: boolean; route_action?: string; reassign_to?: string }) => {
+    deleteRepositoriesWithImpact(repoDeletionImpact([args.id]), args);
+    console.log('[Mock] Deleted repository:', args.id);
   },
   list_repo_tree: async () => {
     return buildRepoTree();
@@ -1,8 +1,9 @@
 import { describe, it, expect, vi } from 'vitest';
-import { mount } from '@vue/test-utils';
+import { mount, flushPromises } from '@vue/test-utils';
 import { createI18n } from 'vue-i18n';
 import Repositories from './Repositories.vue';
 import { ask } from '@tauri-apps/plugin-dialog';
+import { invoke } from '@tauri-apps/api/core';
 
 // Mock tauri invoke
 vi.mock('@tauri-apps/api/core', () => ({
@@ -41,6 +42,15 @@ vi.mock('@/components/repo/ScanImportDialog.vue', () => ({
     },
   },
 }));
+const openImpactDialog = vi.hoisted(() => vi.fn());
+vi.mock('@/components/repo/DeleteImpactDialog.vue', () => ({
+  default: {
+    template: '<div></div>',
+    methods: {
+      open: openImpactDialog,
+    },
+  },
+}));
 vi.mock('@/components/common/ContextMenu.vue', () => ({
   default: {
     template: '<div><slot /></div>',
@@ -198,4 +208,42 @@ describe('Repositories.vue', () => {
       kind: 'warning',
     });
   });
+
+  it('should show the deletion impact and confirm with the chosen route action', async () => {
+    const impact = {
+      repositories: [{ id: 'repo-1', name: 'My Repo' }],
+      groups: [],
+      ungrouped_repositories: [],
+      routes: [{ id: 'route-1', name: 'Sync', source_id: 'repo-1', target_id: 'repo-2', role: 'source' }],
+      task_steps: [],
+      workspace_configs: [],
+    };
+    vi.mocked(invoke).mockImplementation(((cmd: string) =>
+      Promise.resolve(cmd === 'preview_repository_deletion' ? impact : [])) as any);
+    vi.mocked(ask).mockClear();
+    openImpactDialog.mockResolvedValue({ delete_repositories: false, route_action: 'archive', reassign_to: null });
+
+    const wrapper = mount(Repositories, {
+      global: {
+        plugins: [i18n],
+      },
+    });
+
+    (wrapper.vm as any).onContextMenu(new MouseEvent('contextmenu'), { id: 'repo-1', name: 'My Repo' });
+    await wrapper.vm.$nextTick();
+
+    const contextMenu = wrapper.findComponent({ name: 'ContextMenu' });
+    await contextMenu.vm.$emit('select', 'delete');
+    await flushPromises();
+
+    expect(openImpactDialog).toHaveBeenCalledWith(expect.objectContaining({ name: 'My Repo', isGroup: false, impact }));
+    expect(ask).not.toHaveBeenCalled();
+    expect(invoke).toHaveBeenCalledWith('delete_repository', {
+      id: 'repo-1',
+      delete_repositories: false,
+      route_action: 'archive',
+      reassign_to: null,
+      confirm: true,
+    });
+  });
 });
@@ -1,5 +1,5 @@
 import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
-import { mount } from '@vue/test-utils';
+import { mount, flushPromises } from '@vue/test-utils';
 import { createI18n } from 'vue-i18n';
 import Settings from './Settings.vue';
 import { invoke } from '@tauri-apps/api/core';
@@ -13,6 +13,7 @@ vi.mock('@tauri-apps/api/core', () => ({
 // Mock tauri dialog API
 vi.mock('@tauri-apps/plugin-dialog', () => ({
   open: vi.fn(),
+  ask: vi.fn(),
 }));
 
 // Mock useTheme composable
@@ -136,4 +137,25 @@ describe('Settings.vue', () => {
       title: 'Select Git Executable',
     });
   });
+
+  it('should unlock a passphrase-protected vault', async () => {
+    vi.mocked(invoke).mockImplementation((command) => {
+      if (command === 'get_credential_vault_status') {
+        return Promise.resolve({ mode: 'passphrase', unlocked: false });
+      }
+      return Promise.resolve({});
+    });
+    const wrapper = mount(Settings, {
+      global: {
+        plugins: [i18n],
+      },
+    });
+
+    await flushPromises();
+
+    await wrapper.find('#unlock_passphrase').setValue('secret');
+    await wrapper.find('#unlock_passphrase').trigger('keyup.enter');
+
+    expect(invoke).toHaveBeenCalledWith('unlock_credentials', { passphrase: 'secret' });
+  });
 });